@@ -2,6 +2,11 @@ use simulation::delay::SimulatedDelay;
 use simulation::SimulatedPac;
 
 use drivers::spi_device_driver::SpiDeviceDriver;
+use peripheral_traits::digest::{Digest, DigestInit, Sha2_256};
+use peripheral_traits::ecdsa::{EcdsaKeyGen, EcdsaSign, P256};
+use peripheral_traits::sw::ecdsa::EcdsaEngine;
+use peripheral_traits::sw::sha2::Sha2Engine;
+use rand::rngs::OsRng;
 pub fn main() {
     let pac = SimulatedPac::new();
     let config = drivers::spi_device_driver::Config::default();
@@ -15,5 +20,19 @@ pub fn main() {
     );
     driver.init().unwrap();
 
-    driver.write(&[1, 2, 3]).unwrap();
+    let payload = [1, 2, 3];
+    let mut hash_engine = Sha2Engine;
+    let mut digest = hash_engine.init(Sha2_256).unwrap();
+    digest.update(&payload).unwrap();
+    let payload_digest = digest.finalize().unwrap();
+
+    let mut ecdsa_engine = EcdsaEngine::<P256, _>::new(OsRng);
+    let (private_key, _public_key) = ecdsa_engine.generate_key_pair(&P256).unwrap();
+    let signature = ecdsa_engine
+        .sign::<Sha2_256>(&P256, &private_key, payload_digest)
+        .unwrap();
+
+    driver.write(&payload).unwrap();
+    driver.write(payload_digest.as_ref()).unwrap();
+    driver.write(&signature.to_bytes()).unwrap();
 }
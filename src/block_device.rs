@@ -1,4 +1,4 @@
-
+use crate::digest::Digest;
 
 pub enum ErrorKind {
     ReadError,
@@ -46,4 +46,79 @@ pub trait BlockDevice: ErrorType {
 
     /// Size of the underlying device in bytes
     fn  capacity(&self) -> usize;
-}
\ No newline at end of file
+}
+
+/// Error returned by [`hash_region`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum HashRegionError<BlockError, DigestError> {
+    /// Underlying block device error
+    BlockDevice(BlockError),
+    /// Underlying digest error
+    Digest(DigestError),
+
+    /// The region extends past the end of the device
+    OutOfBounds,
+    /// The scratch buffer cannot hold a single readable block
+    ScratchTooSmall,
+}
+
+/// Feed a byte range of a block device into a running digest computation.
+///
+/// The device is read in multiples of [`BlockDevice::read_size`] through the
+/// caller-supplied scratch buffer, so neither `address` nor `length` need to be
+/// aligned. Only the requested bytes are hashed; the computation is not
+/// finalized. If the capacity is not a multiple of the read size, a read
+/// reaching the end of the device is shortened to end there.
+///
+/// # Parameters
+///
+/// - `device`: The block device to read from.
+/// - `digest`: The digest computation to update.
+/// - `address`: Byte address of the first byte to hash.
+/// - `length`: Number of bytes to hash.
+/// - `scratch`: Buffer used for reads. Must hold at least one readable block;
+///   larger buffers reduce the number of reads.
+pub fn hash_region<B, D>(
+    device: &mut B,
+    digest: &mut D,
+    address: usize,
+    length: usize,
+    scratch: &mut [u8],
+) -> Result<(), HashRegionError<B::Error, D::Error>>
+where
+    B: BlockDevice,
+    D: Digest,
+{
+    let capacity = device.capacity();
+    let end = address
+        .checked_add(length)
+        .filter(|end| *end <= capacity)
+        .ok_or(HashRegionError::OutOfBounds)?;
+
+    let read_size = device.read_size().max(1);
+    let chunk_size = scratch.len() - scratch.len() % read_size;
+    if chunk_size == 0 {
+        return Err(HashRegionError::ScratchTooSmall);
+    }
+
+    let mut block_addr = address - address % read_size;
+    let mut skip = address - block_addr;
+    while block_addr < end {
+        let remaining =
+            ((end - block_addr).div_ceil(read_size) * read_size).min(capacity - block_addr);
+        let chunk = &mut scratch[..chunk_size.min(remaining)];
+        device
+            .read(block_addr, chunk)
+            .map_err(HashRegionError::BlockDevice)?;
+
+        let stop = chunk.len().min(end - block_addr);
+        digest
+            .update(&chunk[skip..stop])
+            .map_err(HashRegionError::Digest)?;
+
+        block_addr += chunk.len();
+        skip = 0;
+    }
+
+    Ok(())
+}
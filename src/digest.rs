@@ -40,6 +40,9 @@ pub enum ErrorKind {
 
     /// The hash computation context has not been initialized.
     NotInitialized,
+
+    /// A saved hash computation state is malformed or belongs to a different algorithm.
+    InvalidState,
 }
 
 pub trait Error: core::fmt::Debug {
@@ -57,35 +60,198 @@ impl Error for core::convert::Infallible {
     }
 }
 
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
 pub trait ErrorType {
     /// Error type.
     type Error: Error;
 }
 
-pub trait Digest: ErrorType {
-    type InitParams;
+/// A fixed-size digest value.
+///
+/// `N` is the output size in bytes. The value is a plain byte array wrapper so
+/// that it can be default-initialized and copied regardless of its size.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Output<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Output<N> {
+    /// Returns the digest as a byte array.
+    pub fn into_array(self) -> [u8; N] {
+        self.0
+    }
+}
+
+impl<const N: usize> Default for Output<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Output<N> {
+    fn from(value: [u8; N]) -> Self {
+        Self(value)
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Output<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsMut<[u8]> for Output<N> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+/// A hash algorithm with a fixed-size output.
+///
+/// Implemented by zero-sized marker types so that the algorithm, and with it
+/// the size of the produced digest, is known at compile time.
+pub trait DigestAlgorithm {
+    /// Size of the digest output in bits.
+    const OUTPUT_BITS: usize;
+
+    /// Size of the digest output in bytes.
+    const OUTPUT_SIZE: usize = Self::OUTPUT_BITS / 8;
+
+    /// Size in bytes of the blocks the input is processed in.
+    const BLOCK_SIZE: usize;
+
+    /// Content bytes of the DER encoding of the algorithm's ASN.1 object identifier.
+    ///
+    /// Identifies the algorithm where the output size alone does not, for
+    /// example to tell SHA-256 from SHA3-256.
+    const OID: &'static [u8];
+
+    /// Type of the digest value produced by this algorithm.
+    type DigestOutput: AsRef<[u8]> + AsMut<[u8]> + Default + Copy + Debug + Eq;
+}
+
+macro_rules! digest_algorithm {
+    ($(#[$meta:meta])* $name:ident, $bits:expr, $block_size:expr, oid: $oid:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $name;
+
+        impl DigestAlgorithm for $name {
+            const OUTPUT_BITS: usize = $bits;
+            const BLOCK_SIZE: usize = $block_size;
+            const OID: &'static [u8] = &$oid;
+            type DigestOutput = Output<{ $bits / 8 }>;
+        }
+    };
+}
+
+digest_algorithm!(
+    /// SHA-224 (FIPS 180-4), OID 2.16.840.1.101.3.4.2.4.
+    Sha2_224, 224, 64,
+    oid: [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x04]
+);
+digest_algorithm!(
+    /// SHA-256 (FIPS 180-4), OID 2.16.840.1.101.3.4.2.1.
+    Sha2_256, 256, 64,
+    oid: [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01]
+);
+digest_algorithm!(
+    /// SHA-384 (FIPS 180-4), OID 2.16.840.1.101.3.4.2.2.
+    Sha2_384, 384, 128,
+    oid: [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02]
+);
+digest_algorithm!(
+    /// SHA-512 (FIPS 180-4), OID 2.16.840.1.101.3.4.2.3.
+    Sha2_512, 512, 128,
+    oid: [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03]
+);
+digest_algorithm!(
+    /// SHA3-224 (FIPS 202), OID 2.16.840.1.101.3.4.2.7.
+    Sha3_224, 224, 144,
+    oid: [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x07]
+);
+digest_algorithm!(
+    /// SHA3-256 (FIPS 202), OID 2.16.840.1.101.3.4.2.8.
+    Sha3_256, 256, 136,
+    oid: [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x08]
+);
+digest_algorithm!(
+    /// SHA3-384 (FIPS 202), OID 2.16.840.1.101.3.4.2.9.
+    Sha3_384, 384, 104,
+    oid: [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x09]
+);
+digest_algorithm!(
+    /// SHA3-512 (FIPS 202), OID 2.16.840.1.101.3.4.2.10.
+    Sha3_512, 512, 72,
+    oid: [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0a]
+);
+
+/// A hash engine that can start digest computations.
+///
+/// Starting a computation borrows the engine exclusively and hands back an
+/// operation context on which the data is hashed. While the context is alive
+/// the borrow checker rejects any other use of the engine, so two computations
+/// can never interleave on one accelerator.
+pub trait DigestInit<A: DigestAlgorithm>: ErrorType {
+    /// The operation context returned by [`DigestInit::init`].
+    ///
+    /// Implementations should release any hardware resources held by the
+    /// operation when the context is dropped.
+    type OpContext<'a>: Digest<Algorithm = A> + ErrorType<Error = Self::Error>
+    where
+        Self: 'a;
 
     /// Init instance of the crypto function with the given context.
     ///
     /// # Parameters
     ///
-    /// - `init_params`: The context or configuration parameters for the crypto function.
+    /// - `init_params`: The hash algorithm to compute.
     ///
     /// # Returns
     ///
-    /// A new instance of the hash function.    
-    fn init(init_params: Self::InitParams) -> Result<(), Self::Error>;
+    /// An operation context borrowing the engine until it is dropped.
+    fn init(&mut self, init_params: A) -> Result<Self::OpContext<'_>, Self::Error>;
+}
+
+/// A running digest computation.
+pub trait Digest: ErrorType {
+    /// The hash algorithm computed by this implementation.
+    type Algorithm: DigestAlgorithm;
 
     /// Update state using provided input data.
     ///
     /// # Parameters
     ///
-    /// - `input`: The input data to be hashed. This can be any type that implements `AsRef<[u8]>`.
+    /// - `input`: The input data to be hashed. The data is only read, so it may live in read-only memory.
     ///
     /// # Returns
     ///
     /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns a `CryptoError`.    
-    fn update(&mut self, input: &mut [u8]) -> Result<(), Self::Error>;
+    fn update(&mut self, input: &[u8]) -> Result<(), Self::Error>;
+
+    /// Update state using several input slices, in order.
+    ///
+    /// The result is the same as calling [`update`](Self::update) on each slice
+    /// in turn. Implementations backed by a DMA-capable engine should override
+    /// this to process the slices as one scatter-gather list, without copying
+    /// them into a contiguous buffer.
+    ///
+    /// # Parameters
+    ///
+    /// - `inputs`: The input slices to be hashed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns a `CryptoError`.
+    fn update_vectored(&mut self, inputs: &[&[u8]]) -> Result<(), Self::Error> {
+        for input in inputs {
+            self.update(input)?;
+        }
+        Ok(())
+    }
 
     /// Reset instance to its initial state.
     ///
@@ -96,12 +262,57 @@ pub trait Digest: ErrorType {
 
     /// Finalize the computation and produce the output.
     ///
+    /// # Returns
+    ///
+    /// A `Result` containing the digest value, sized for `Self::Algorithm`. On failure, returns a `CryptoError`.
+    fn finalize(
+        &mut self,
+    ) -> Result<<Self::Algorithm as DigestAlgorithm>::DigestOutput, Self::Error>;
+}
+
+/// Export of the intermediate state of a running digest computation.
+///
+/// This allows a hash engine to be time-sliced between several users: a
+/// computation is saved, its context dropped to release the engine, and the
+/// computation later resumed through [`DigestRestore::restore`], possibly on
+/// another instance of the same engine.
+///
+/// The layout of the saved state is defined by the implementation. It is only
+/// guaranteed to be accepted by the implementation that produced it.
+pub trait DigestSave: Digest {
+    /// Size in bytes of a saved state.
+    const SAVED_STATE_SIZE: usize;
+
+    /// Serialize the intermediate state of the computation.
+    ///
+    /// The computation is left untouched and can be continued afterwards.
+    ///
     /// # Parameters
     ///
-    /// - `out`: A mutable slice to store the hash output. The length of the slice must be at least `MAX_OUTPUT_SIZE`.
+    /// - `state`: A mutable slice to store the state. The length of the slice must be at least `SAVED_STATE_SIZE`.
     ///
     /// # Returns
     ///
-    /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns a `CryptoError`.    
-    fn finalize(&mut self, out: &mut [u8]) -> Result<(), Self::Error>;
+    /// A `Result` containing the number of bytes written. On failure, returns a `CryptoError`.
+    fn save(&mut self, state: &mut [u8]) -> Result<usize, Self::Error>;
+}
+
+/// Resumption of a digest computation from a state saved with [`DigestSave::save`].
+pub trait DigestRestore<A: DigestAlgorithm>: DigestInit<A> {
+    /// Start a computation from a previously saved state.
+    ///
+    /// # Parameters
+    ///
+    /// - `init_params`: The hash algorithm the state was saved for.
+    /// - `state`: The saved state.
+    ///
+    /// # Returns
+    ///
+    /// An operation context borrowing the engine until it is dropped. Fails with
+    /// [`ErrorKind::InvalidState`] if the state is malformed.
+    fn restore(
+        &mut self,
+        init_params: A,
+        state: &[u8],
+    ) -> Result<Self::OpContext<'_>, Self::Error>;
 }
@@ -0,0 +1,183 @@
+//! Elliptic-curve Diffie-Hellman key agreement.
+//!
+//! The traits mirror the ECDSA ones: the key, curve and shared secret types
+//! are associated types of the backend. A backend that must not expose the
+//! shared secret can return a [`SlotId`](crate::key::SlotId) or similar handle
+//! as its [`EcdhTypes::SharedSecret`], which key derivation then uses through a
+//! [`KeyHandle`](crate::key::KeyHandle).
+
+use crate::ecdsa::EcdsaCurve;
+use crate::key::Validated;
+
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic error kind
+    ///
+    /// By using this method, errors freely defined by HAL implementations
+    /// can be converted to a set of generic errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+pub trait ErrorType {
+    /// Error type.
+    type Error: Error;
+}
+
+/// Error kind.
+///
+/// This represents a common set of key agreement errors. Implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    Busy,
+    /// The peer public key is not a valid point, or is of small order.
+    InvalidPublicKey,
+    KeyGenError,
+    /// The key agreement failed.
+    AgreementError,
+    /// No key slot is available to hold the shared secret.
+    NoFreeSlot,
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+/// A curve usable for key agreement.
+///
+/// Every [`EcdsaCurve`] is one; Montgomery curves such as [`X25519`] are not
+/// usable for ECDSA and only implement this trait.
+pub trait EcdhCurve {
+    /// Size in bytes of an encoded public key.
+    const PUBLIC_KEY_SIZE: usize;
+
+    /// Size in bytes of the shared secret.
+    const SHARED_SECRET_SIZE: usize;
+
+    /// Content bytes of the DER encoding of the curve's ASN.1 object identifier.
+    const OID: &'static [u8];
+
+    /// Identifier of the curve. The shipped curves use their TLS `NamedGroup` value.
+    fn id() -> u32;
+}
+
+impl<C: EcdsaCurve> EcdhCurve for C {
+    const PUBLIC_KEY_SIZE: usize = C::POINT_SIZE;
+    const SHARED_SECRET_SIZE: usize = C::FIELD_SIZE;
+    const OID: &'static [u8] = C::OID;
+
+    fn id() -> u32 {
+        C::id()
+    }
+}
+
+/// X25519 (RFC 7748), OID 1.3.101.110.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct X25519;
+
+impl EcdhCurve for X25519 {
+    const PUBLIC_KEY_SIZE: usize = 32;
+    const SHARED_SECRET_SIZE: usize = 32;
+    const OID: &'static [u8] = &[0x2b, 0x65, 0x6e];
+
+    fn id() -> u32 {
+        29
+    }
+}
+
+pub trait EcdhTypes {
+    /// The private key, or a reference to it such as a
+    /// [`KeyHandle`](crate::key::KeyHandle) resolved by the backend.
+    type PrivateKey;
+    type PublicKey;
+    /// The result of the key agreement.
+    ///
+    /// Either the shared secret itself, or a handle to it when the backend
+    /// keeps it inside the engine.
+    type SharedSecret;
+    type Curve: EcdhCurve;
+}
+
+/// Trait for ECDH key generation.
+pub trait EcdhKeyGen: ErrorType + EcdhTypes {
+    /// Generates a key pair for key agreement.
+    ///
+    /// # Parameters
+    /// - `curve`: The elliptic curve to use for key generation.
+    ///
+    /// # Returns
+    /// A result containing the generated private and public keys, or an error.
+    fn generate_key_pair(
+        &mut self,
+        curve: &Self::Curve,
+    ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>;
+}
+
+/// Trait for ECDH public key validation.
+///
+/// Peer keys must be validated before use, which rules out invalid-curve and
+/// small-subgroup attacks. [`Ecdh::agree`] only accepts keys that went through
+/// [`validate`](Self::validate).
+pub trait EcdhValidate: ErrorType + EcdhTypes {
+    /// Checks a public key.
+    ///
+    /// For curves that are also [`EcdsaCurve`]s this is the same full
+    /// validation as [`EcdsaValidate::check_public_key`](crate::ecdsa::EcdsaValidate::check_public_key).
+    /// For X25519 the key must not be one of the points of small order.
+    ///
+    /// # Parameters
+    /// - `curve`: The elliptic curve the key belongs to.
+    /// - `public_key`: The public key to check.
+    ///
+    /// # Returns
+    /// `Ok(())` if the key is valid, or an error of kind
+    /// [`ErrorKind::InvalidPublicKey`].
+    fn check_public_key(
+        &mut self,
+        curve: &Self::Curve,
+        public_key: &Self::PublicKey,
+    ) -> Result<(), Self::Error>;
+
+    /// Validates a public key with [`check_public_key`](Self::check_public_key).
+    ///
+    /// # Parameters
+    /// - `curve`: The elliptic curve the key belongs to.
+    /// - `public_key`: The public key to validate.
+    ///
+    /// # Returns
+    /// The validated key, or an error.
+    fn validate(
+        &mut self,
+        curve: &Self::Curve,
+        public_key: Self::PublicKey,
+    ) -> Result<Validated<Self::PublicKey>, Self::Error> {
+        self.check_public_key(curve, &public_key)?;
+        Ok(Validated::new(public_key))
+    }
+}
+
+/// Trait for ECDH key agreement.
+pub trait Ecdh: EcdhValidate {
+    /// Computes the shared secret with a peer.
+    ///
+    /// For X25519, implementations must still reject an all-zero shared secret.
+    ///
+    /// # Parameters
+    /// - `curve`: The elliptic curve both keys belong to.
+    /// - `private_key`: Our private key.
+    /// - `peer_public_key`: The validated public key received from the peer.
+    ///
+    /// # Returns
+    /// A result containing the shared secret or a handle to it, or an error.
+    fn agree(
+        &mut self,
+        curve: &Self::Curve,
+        private_key: &Self::PrivateKey,
+        peer_public_key: &Validated<Self::PublicKey>,
+    ) -> Result<Self::SharedSecret, Self::Error>;
+}
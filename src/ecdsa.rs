@@ -1,5 +1,11 @@
 use core::fmt::Debug;
 
+use crate::digest::DigestAlgorithm;
+use crate::key::{KeyPolicy, SlotId, Validated};
+
+pub mod encoding;
+pub mod message;
+
 pub trait Error: core::fmt::Debug {
     /// Convert error to a generic error kind
     ///
@@ -26,18 +32,187 @@ pub enum ErrorKind {
     InvalidSignature,
     KeyGenError,
     SigningError,
+    /// A public key or signature is not validly encoded.
+    InvalidEncoding,
+    /// The requested nonce mode is not supported by the backend.
+    UnsupportedNonceMode,
+    /// The public key failed validation.
+    InvalidPublicKey,
+    /// No key slot is available to hold a generated key.
+    NoFreeSlot,
+    /// The [`KeyPolicy`] of the key's slot does not allow the operation.
+    PolicyViolation,
     Other,
 }
 
-pub trait HashMarker {
-    fn size() -> usize;
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+/// The hash algorithm that produced a message hash passed to [`EcdsaSign::sign`]
+/// or [`EcdsaVerify::verify`].
+///
+/// Implemented for every [`DigestAlgorithm`], so the digest markers such as
+/// [`Sha2_256`](crate::digest::Sha2_256) name the hash directly.
+pub trait HashMarker: DigestAlgorithm {
+    /// Collision resistance of the hash in bits, half its output size.
+    const SECURITY_BITS: usize = Self::OUTPUT_BITS / 2;
+
+    /// Size in bytes of the message hash.
+    fn size() -> usize {
+        Self::OUTPUT_SIZE
+    }
 }
 
+impl<A: DigestAlgorithm> HashMarker for A {}
+
+/// An elliptic curve.
+///
+/// The sizes and the object identifier are known at compile time, so backends
+/// can restrict their `Curve` type to the curves they support and encoders can
+/// size their buffers statically.
 pub trait EcdsaCurve {
+    /// Size in bytes of a field element, and of each point coordinate.
+    const FIELD_SIZE: usize;
+
+    /// Size in bytes of a scalar, such as a private key or a signature component.
+    const SCALAR_SIZE: usize;
+
+    /// Size in bytes of an uncompressed SEC1 point, `0x04 || x || y`.
+    const POINT_SIZE: usize = 1 + 2 * Self::FIELD_SIZE;
+
+    /// Size in bytes of a compressed SEC1 point, `0x02 or 0x03 || x`.
+    const COMPRESSED_POINT_SIZE: usize = 1 + Self::FIELD_SIZE;
+
+    /// The field prime `p`, big-endian, [`FIELD_SIZE`](Self::FIELD_SIZE) bytes.
+    const PRIME: &'static [u8];
+
+    /// The order `n` of the base point, big-endian, [`SCALAR_SIZE`](Self::SCALAR_SIZE) bytes.
+    const ORDER: &'static [u8];
+
+    /// Content bytes of the DER encoding of the curve's ASN.1 object identifier.
+    const OID: &'static [u8];
+
+    /// Security strength of the curve in bits, following NIST SP 800-57.
+    const SECURITY_BITS: usize;
+
+    /// Identifier of the curve. The shipped curves use their TLS `NamedGroup` value.
     fn id() -> u32;
 }
 
+/// Decodes a big-endian constant written in hex, at compile time.
+const fn from_hex<const N: usize>(hex: &str) -> [u8; N] {
+    const fn nibble(c: u8) -> u8 {
+        match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            _ => panic!("invalid hex digit"),
+        }
+    }
+
+    let hex = hex.as_bytes();
+    assert!(hex.len() == 2 * N);
+    let mut out = [0u8; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = (nibble(hex[2 * i]) << 4) | nibble(hex[2 * i + 1]);
+        i += 1;
+    }
+    out
+}
+
+macro_rules! ecdsa_curve {
+    (
+        $(#[$meta:meta])* $name:ident, field: $field:expr, scalar: $scalar:expr,
+        prime: $prime:expr, order: $order:expr, oid: $oid:expr, security: $security:expr,
+        id: $id:expr
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $name;
+
+        impl EcdsaCurve for $name {
+            const FIELD_SIZE: usize = $field;
+            const SCALAR_SIZE: usize = $scalar;
+            const PRIME: &'static [u8] = &from_hex::<{ $field }>($prime);
+            const ORDER: &'static [u8] = &from_hex::<{ $scalar }>($order);
+            const OID: &'static [u8] = &$oid;
+            const SECURITY_BITS: usize = $security;
+
+            fn id() -> u32 {
+                $id
+            }
+        }
+    };
+}
+
+ecdsa_curve!(
+    /// NIST P-256 (secp256r1), OID 1.2.840.10045.3.1.7.
+    P256, field: 32, scalar: 32,
+    prime: "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+    order: "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+    oid: [0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07],
+    security: 128, id: 23
+);
+ecdsa_curve!(
+    /// NIST P-384 (secp384r1), OID 1.3.132.0.34.
+    P384, field: 48, scalar: 48,
+    prime: "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe\
+            ffffffff0000000000000000ffffffff",
+    order: "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf\
+            581a0db248b0a77aecec196accc52973",
+    oid: [0x2b, 0x81, 0x04, 0x00, 0x22],
+    security: 192, id: 24
+);
+ecdsa_curve!(
+    /// NIST P-521 (secp521r1), OID 1.3.132.0.35.
+    P521, field: 66, scalar: 66,
+    prime: "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+            ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+            ffff",
+    order: "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+            fffa51868783bf2f966b7fcc0148f709a5d03bb5c9b8899c47aebb6fb71e9138\
+            6409",
+    oid: [0x2b, 0x81, 0x04, 0x00, 0x23],
+    security: 256, id: 25
+);
+ecdsa_curve!(
+    /// brainpoolP256r1 (RFC 5639), OID 1.3.36.3.3.2.8.1.1.7.
+    BrainpoolP256r1, field: 32, scalar: 32,
+    prime: "a9fb57dba1eea9bc3e660a909d838d726e3bf623d52620282013481d1f6e5377",
+    order: "a9fb57dba1eea9bc3e660a909d838d718c397aa3b561a6f7901e0e82974856a7",
+    oid: [0x2b, 0x24, 0x03, 0x03, 0x02, 0x08, 0x01, 0x01, 0x07],
+    security: 128, id: 26
+);
+ecdsa_curve!(
+    /// brainpoolP384r1 (RFC 5639), OID 1.3.36.3.3.2.8.1.1.11.
+    BrainpoolP384r1, field: 48, scalar: 48,
+    prime: "8cb91e82a3386d280f5d6f7e50e641df152f7109ed5456b412b1da197fb71123\
+            acd3a729901d1a71874700133107ec53",
+    order: "8cb91e82a3386d280f5d6f7e50e641df152f7109ed5456b31f166e6cac0425a7\
+            cf3ab6af6b7fc3103b883202e9046565",
+    oid: [0x2b, 0x24, 0x03, 0x03, 0x02, 0x08, 0x01, 0x01, 0x0b],
+    security: 192, id: 27
+);
+ecdsa_curve!(
+    /// brainpoolP512r1 (RFC 5639), OID 1.3.36.3.3.2.8.1.1.13.
+    BrainpoolP512r1, field: 64, scalar: 64,
+    prime: "aadd9db8dbe9c48b3fd4e6ae33c9fc07cb308db3b3c9d20ed6639cca70330871\
+            7d4d9b009bc66842aecda12ae6a380e62881ff2f2d82c68528aa6056583a48f3",
+    order: "aadd9db8dbe9c48b3fd4e6ae33c9fc07cb308db3b3c9d20ed6639cca70330870\
+            553e5c414ca92619418661197fac10471db1d381085ddaddb58796829ca90069",
+    oid: [0x2b, 0x24, 0x03, 0x03, 0x02, 0x08, 0x01, 0x01, 0x0d],
+    security: 256, id: 28
+);
+
 pub trait EcdsaTypes {
+    /// The private key, or a reference to it.
+    ///
+    /// Backends that keep keys in a key vault can use a
+    /// [`SlotId`] or [`KeyHandle`](crate::key::KeyHandle)
+    /// here, so the key never leaves the vault.
     type PrivateKey;
     type PublicKey;
     type Signature;
@@ -48,6 +223,8 @@ pub trait EcdsaTypes {
 /// Trait for ECDSA key generation.
 ///
 /// This trait defines the methods required for generating ECDSA key pairs.
+/// The methods of this and the other ECDSA traits take the implementation by
+/// `&mut self`, so a backend can own the PKA engine or RNG it drives.
 pub trait EcdsaKeyGen: ErrorType + EcdsaTypes {
 
     /// Generates an ECDSA key pair.
@@ -58,14 +235,64 @@ pub trait EcdsaKeyGen: ErrorType + EcdsaTypes {
     /// # Returns
     /// A result containing the generated private and public keys, or an error.    
     fn generate_key_pair(
+        &mut self,
         curve: &Self::Curve,
     ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>;
 }
 
+/// Trait for ECDSA key generation inside the backend's key vault.
+///
+/// Unlike [`EcdsaKeyGen`], the private key is written to a slot and never
+/// returned, so it does not have to be exportable. The slot keeps the
+/// [`KeyPolicy`] the key was generated with, and the backend fails operations
+/// the policy does not allow with [`ErrorKind::PolicyViolation`].
+pub trait EcdsaKeyGenInSlot: ErrorType + EcdsaTypes {
+    /// Generates an ECDSA key pair into a free slot.
+    ///
+    /// # Parameters
+    /// - `curve`: The elliptic curve to use for key generation.
+    /// - `policy`: The uses allowed for the private key.
+    ///
+    /// # Returns
+    /// A result containing the slot holding the private key and the public
+    /// key, or an error of kind [`ErrorKind::NoFreeSlot`] if the vault is full.
+    fn generate_key_in_slot(
+        &mut self,
+        curve: &Self::Curve,
+        policy: KeyPolicy,
+    ) -> Result<(SlotId, Self::PublicKey), Self::Error>;
+}
+
+/// Trait for copying private keys out of the backend's key vault.
+///
+/// Backends that never let keys leave the vault do not implement this trait.
+pub trait EcdsaKeyExport: ErrorType + EcdsaTypes {
+    /// Exports the private key held in a slot.
+    ///
+    /// # Parameters
+    /// - `curve`: The elliptic curve of the key.
+    /// - `slot`: The slot holding the key.
+    /// - `out`: Buffer receiving the private key, big-endian,
+    ///   [`EcdsaCurve::SCALAR_SIZE`] bytes.
+    ///
+    /// # Returns
+    /// The number of bytes written, or an error of kind
+    /// [`ErrorKind::PolicyViolation`] if the key's [`KeyPolicy`] does not
+    /// allow export.
+    fn export_private_key(
+        &mut self,
+        curve: &Self::Curve,
+        slot: SlotId,
+        out: &mut [u8],
+    ) -> Result<usize, Self::Error>;
+}
+
 /// Trait for ECDSA signing.
 ///
 /// This trait defines the methods required for signing messages using ECDSA.
 pub trait EcdsaSign: ErrorType {
+    /// The private key, or a reference to it such as a
+    /// [`KeyHandle`](crate::key::KeyHandle) resolved by the backend.
     type PrivateKey;
     type Curve: EcdsaCurve;
     type Signature;
@@ -75,38 +302,147 @@ pub trait EcdsaSign: ErrorType {
     /// # Parameters
     /// - `curve`: The elliptic curve to use for signing.
     /// - `private_key`: The private key to use for signing.
-    /// - `message_hash`: The hash of the message to sign.
+    /// - `message_hash`: The hash of the message to sign, computed with `H`.
     ///
     /// # Returns
     /// A result containing the generated signature, or an error.    
     fn sign<H: HashMarker>(
+        &mut self,
         curve: &Self::Curve,
         private_key: &Self::PrivateKey,
         message_hash: impl AsRef<[u8]>,
     ) -> Result<Self::Signature, Self::Error>;
 }
 
-/// Trait for ECDSA verification.
+/// How the per-signature nonce `k` is generated.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum NonceMode<'a> {
+    /// `k` is drawn from the backend's random number generator.
+    Random,
+    /// `k` is derived from the private key and the message hash as in RFC 6979.
+    Deterministic,
+    /// RFC 6979 with the caller's fresh entropy as additional input (section 3.6).
+    ///
+    /// The nonce stays secret even if the entropy source is weak, and two
+    /// signatures of the same message differ.
+    Hedged { extra_entropy: &'a [u8] },
+}
+
+/// The nonce modes a backend supports.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct NonceModes {
+    pub random: bool,
+    pub deterministic: bool,
+    pub hedged: bool,
+}
+
+impl NonceModes {
+    /// Whether `mode` is one of the supported modes.
+    pub const fn supports(&self, mode: &NonceMode<'_>) -> bool {
+        match mode {
+            NonceMode::Random => self.random,
+            NonceMode::Deterministic => self.deterministic,
+            NonceMode::Hedged { .. } => self.hedged,
+        }
+    }
+}
+
+/// Trait for ECDSA signing with a nonce mode chosen by the caller.
 ///
-/// This trait defines the methods required for verifying ECDSA signatures.
-pub trait EcdsaVerify: ErrorType {
+/// [`EcdsaSign::sign`] uses the backend's default mode. Software helpers for
+/// the deterministic and hedged modes are in [`rfc6979`](crate::rfc6979).
+pub trait EcdsaSignWithNonce: EcdsaSign {
+    /// The modes accepted by [`sign_with_nonce`](Self::sign_with_nonce).
+    const NONCE_MODES: NonceModes;
+
+    /// Signs a message hash, generating the nonce as requested.
+    ///
+    /// # Parameters
+    /// - `curve`: The elliptic curve to use for signing.
+    /// - `private_key`: The private key to use for signing.
+    /// - `message_hash`: The hash of the message to sign, computed with `H`.
+    /// - `nonce`: How to generate the nonce. Modes missing from
+    ///   [`NONCE_MODES`](Self::NONCE_MODES) fail with
+    ///   [`ErrorKind::UnsupportedNonceMode`].
+    ///
+    /// # Returns
+    /// A result containing the generated signature, or an error.
+    fn sign_with_nonce<H: HashMarker>(
+        &mut self,
+        curve: &Self::Curve,
+        private_key: &Self::PrivateKey,
+        message_hash: impl AsRef<[u8]>,
+        nonce: NonceMode<'_>,
+    ) -> Result<Self::Signature, Self::Error>;
+}
+
+/// Trait for ECDSA public key validation.
+///
+/// Keys received from a host or taken from a certificate must be validated
+/// before use, which rules out invalid-curve attacks. [`EcdsaVerify::verify`]
+/// only accepts keys that went through [`validate`](Self::validate).
+pub trait EcdsaValidate: ErrorType {
     type PublicKey;
     type Curve: EcdsaCurve;
+
+    /// Checks a public key, following the full validation of NIST SP 800-56A
+    /// section 5.6.2.3.3.
+    ///
+    /// The point must not be the identity, its coordinates must be below the
+    /// field prime and it must lie on the curve. All supported curves have
+    /// cofactor 1, so every such point has order `n`.
+    ///
+    /// # Parameters
+    /// - `curve`: The elliptic curve the key belongs to.
+    /// - `public_key`: The public key to check.
+    ///
+    /// # Returns
+    /// `Ok(())` if the key is valid, or an error of kind
+    /// [`ErrorKind::InvalidPublicKey`].
+    fn check_public_key(
+        &mut self,
+        curve: &Self::Curve,
+        public_key: &Self::PublicKey,
+    ) -> Result<(), Self::Error>;
+
+    /// Validates a public key with [`check_public_key`](Self::check_public_key).
+    ///
+    /// # Parameters
+    /// - `curve`: The elliptic curve the key belongs to.
+    /// - `public_key`: The public key to validate.
+    ///
+    /// # Returns
+    /// The validated key, or an error.
+    fn validate(
+        &mut self,
+        curve: &Self::Curve,
+        public_key: Self::PublicKey,
+    ) -> Result<Validated<Self::PublicKey>, Self::Error> {
+        self.check_public_key(curve, &public_key)?;
+        Ok(Validated::new(public_key))
+    }
+}
+
+/// Trait for ECDSA verification.
+///
+/// This trait defines the methods required for verifying ECDSA signatures.
+pub trait EcdsaVerify: EcdsaValidate {
     type Signature;
 
     /// Verifies an ECDSA signature.
     ///
     /// # Parameters
     /// - `curve`: The elliptic curve to use for verification.
-    /// - `public_key`: The public key to use for verification.
-    /// - `message_hash`: The hash of the message to verify.
+    /// - `public_key`: The validated public key to use for verification.
+    /// - `message_hash`: The hash of the message to verify, computed with `H`.
     /// - `signature`: The signature to verify.
     ///
     /// # Returns
     /// A result indicating whether the signature is valid, or an error.    
     fn verify<H: HashMarker>(
+        &mut self,
         curve: &Self::Curve,
-        public_key: &Self::PublicKey,
+        public_key: &Validated<Self::PublicKey>,
         message_hash: impl AsRef<[u8]>,
         signature: &Self::Signature,
     ) -> Result<(), Self::Error>;
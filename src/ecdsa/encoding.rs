@@ -0,0 +1,383 @@
+//! Encodings of ECDSA public keys and signatures.
+//!
+//! Public keys use the SEC1 point encoding, compressed or uncompressed.
+//! Signatures are either the fixed-width `r || s` produced by most PKA engines
+//! or the DER `ECDSA-Sig-Value` used by X.509 and TLS.
+//!
+//! The parsers are strict. They reject hybrid SEC1 points, coordinates not
+//! below the field prime, signature components outside `[1, n - 1]`, and DER
+//! that is not in its unique minimal form. Whether a point lies on the curve
+//! needs the curve arithmetic and is left to the backend.
+
+use super::{EcdsaCurve, ErrorKind};
+
+/// Largest scalar size of the supported curves, that of P-521.
+pub const MAX_SCALAR_SIZE: usize = 66;
+
+const SEC1_IDENTITY: u8 = 0x00;
+const SEC1_COMPRESSED_EVEN: u8 = 0x02;
+const SEC1_COMPRESSED_ODD: u8 = 0x03;
+const SEC1_UNCOMPRESSED: u8 = 0x04;
+
+const DER_INTEGER: u8 = 0x02;
+const DER_SEQUENCE: u8 = 0x30;
+
+/// Error returned when encoding or decoding a public key or signature.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Error {
+    /// The input length does not match the encoding or the curve
+    InvalidLength,
+    /// Unknown SEC1 prefix byte or unexpected DER tag
+    InvalidFormat,
+    /// A DER length or integer is not in its minimal form
+    NonCanonical,
+    /// A coordinate is not below the field prime, or a signature component
+    /// is not in `[1, n - 1]`
+    OutOfRange,
+    /// The SEC1 encoding of the point at infinity
+    Identity,
+    /// The point is not on the curve
+    NotOnCurve,
+    /// The output buffer is too small
+    BufferTooSmall,
+}
+
+impl super::Error for Error {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::InvalidEncoding
+    }
+}
+
+/// Compares two big-endian integers of the same length.
+fn less_than(a: &[u8], b: &[u8]) -> bool {
+    debug_assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b) {
+        if a != b {
+            return a < b;
+        }
+    }
+    false
+}
+
+fn check_coordinate<C: EcdsaCurve>(coordinate: &[u8]) -> Result<(), Error> {
+    if coordinate.len() != C::FIELD_SIZE {
+        return Err(Error::InvalidLength);
+    }
+    if !less_than(coordinate, C::PRIME) {
+        return Err(Error::OutOfRange);
+    }
+    Ok(())
+}
+
+fn check_scalar<C: EcdsaCurve>(scalar: &[u8]) -> Result<(), Error> {
+    if scalar.iter().all(|&b| b == 0) || !less_than(scalar, C::ORDER) {
+        return Err(Error::OutOfRange);
+    }
+    Ok(())
+}
+
+/// A SEC1-encoded elliptic curve point, borrowing its coordinates.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Sec1Point<'a> {
+    /// `0x04 || x || y`.
+    Uncompressed { x: &'a [u8], y: &'a [u8] },
+    /// `0x02 || x` or `0x03 || x`, depending on the parity of `y`.
+    Compressed { x: &'a [u8], y_is_odd: bool },
+}
+
+impl<'a> Sec1Point<'a> {
+    /// Parses a SEC1 point.
+    ///
+    /// # Parameters
+    ///
+    /// - `bytes`: The encoded point, exactly [`EcdsaCurve::POINT_SIZE`] or
+    ///   [`EcdsaCurve::COMPRESSED_POINT_SIZE`] bytes.
+    ///
+    /// # Returns
+    ///
+    /// The point, with coordinates checked to be below the field prime.
+    pub fn parse<C: EcdsaCurve>(bytes: &'a [u8]) -> Result<Self, Error> {
+        let (&prefix, coordinates) = bytes.split_first().ok_or(Error::InvalidLength)?;
+        let point = match prefix {
+            SEC1_IDENTITY => return Err(Error::Identity),
+            SEC1_UNCOMPRESSED => {
+                if bytes.len() != C::POINT_SIZE {
+                    return Err(Error::InvalidLength);
+                }
+                let (x, y) = coordinates.split_at(C::FIELD_SIZE);
+                Sec1Point::Uncompressed { x, y }
+            }
+            SEC1_COMPRESSED_EVEN | SEC1_COMPRESSED_ODD => {
+                if bytes.len() != C::COMPRESSED_POINT_SIZE {
+                    return Err(Error::InvalidLength);
+                }
+                Sec1Point::Compressed {
+                    x: coordinates,
+                    y_is_odd: prefix == SEC1_COMPRESSED_ODD,
+                }
+            }
+            _ => return Err(Error::InvalidFormat),
+        };
+        point.check::<C>()?;
+        Ok(point)
+    }
+
+    fn check<C: EcdsaCurve>(&self) -> Result<(), Error> {
+        match *self {
+            Sec1Point::Uncompressed { x, y } => {
+                check_coordinate::<C>(x)?;
+                check_coordinate::<C>(y)
+            }
+            Sec1Point::Compressed { x, .. } => check_coordinate::<C>(x),
+        }
+    }
+
+    /// The x-coordinate of the point.
+    pub fn x(&self) -> &'a [u8] {
+        match *self {
+            Sec1Point::Uncompressed { x, .. } | Sec1Point::Compressed { x, .. } => x,
+        }
+    }
+
+    /// Encodes the point.
+    ///
+    /// # Parameters
+    ///
+    /// - `out`: Buffer receiving the encoding.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written.
+    pub fn encode<C: EcdsaCurve>(&self, out: &mut [u8]) -> Result<usize, Error> {
+        self.check::<C>()?;
+        let len = match self {
+            Sec1Point::Uncompressed { .. } => C::POINT_SIZE,
+            Sec1Point::Compressed { .. } => C::COMPRESSED_POINT_SIZE,
+        };
+        let out = out.get_mut(..len).ok_or(Error::BufferTooSmall)?;
+        match *self {
+            Sec1Point::Uncompressed { x, y } => {
+                out[0] = SEC1_UNCOMPRESSED;
+                out[1..1 + C::FIELD_SIZE].copy_from_slice(x);
+                out[1 + C::FIELD_SIZE..].copy_from_slice(y);
+            }
+            Sec1Point::Compressed { x, y_is_odd } => {
+                out[0] = if y_is_odd {
+                    SEC1_COMPRESSED_ODD
+                } else {
+                    SEC1_COMPRESSED_EVEN
+                };
+                out[1..].copy_from_slice(x);
+            }
+        }
+        Ok(len)
+    }
+}
+
+/// Size in bytes of a raw `r || s` signature on curve `C`.
+pub const fn raw_signature_size<C: EcdsaCurve>() -> usize {
+    2 * C::SCALAR_SIZE
+}
+
+/// Largest size in bytes of a DER signature on curve `C`.
+pub const fn max_der_signature_size<C: EcdsaCurve>() -> usize {
+    // Each INTEGER may need a leading zero byte to stay positive.
+    let content = 2 * (2 + C::SCALAR_SIZE + 1);
+    if content < 0x80 {
+        2 + content
+    } else {
+        3 + content
+    }
+}
+
+/// Reads one DER tag-length header and returns the value and the remaining input.
+fn read_tlv(input: &[u8], tag: u8) -> Result<(&[u8], &[u8]), Error> {
+    match input {
+        [] | [_] => Err(Error::InvalidLength),
+        [t, ..] if *t != tag => Err(Error::InvalidFormat),
+        [_, len, rest @ ..] => {
+            let (len, rest) = match *len {
+                len @ 0..=0x7f => (len as usize, rest),
+                0x81 => match rest {
+                    [len, rest @ ..] if *len >= 0x80 => (*len as usize, rest),
+                    [_, ..] => return Err(Error::NonCanonical),
+                    [] => return Err(Error::InvalidLength),
+                },
+                // No ECDSA signature needs more than one length byte.
+                _ => return Err(Error::InvalidLength),
+            };
+            if rest.len() < len {
+                return Err(Error::InvalidLength);
+            }
+            Ok(rest.split_at(len))
+        }
+    }
+}
+
+/// Reads a DER INTEGER into a big-endian scalar of `C::SCALAR_SIZE` bytes.
+fn read_integer<'i, C: EcdsaCurve>(input: &'i [u8], out: &mut [u8]) -> Result<&'i [u8], Error> {
+    let (value, rest) = read_tlv(input, DER_INTEGER)?;
+    let value = match value {
+        [] => return Err(Error::InvalidLength),
+        [first, ..] if first & 0x80 != 0 => return Err(Error::OutOfRange),
+        [0, second, ..] if second & 0x80 == 0 => return Err(Error::NonCanonical),
+        [0, value @ ..] if !value.is_empty() => value,
+        value => value,
+    };
+    if value.len() > C::SCALAR_SIZE {
+        return Err(Error::OutOfRange);
+    }
+    let (zeros, scalar) = out.split_at_mut(C::SCALAR_SIZE - value.len());
+    zeros.fill(0);
+    scalar.copy_from_slice(value);
+    check_scalar::<C>(out)?;
+    Ok(rest)
+}
+
+/// Writes a big-endian scalar as a DER INTEGER and returns the number of bytes written.
+fn write_integer(scalar: &[u8], out: &mut [u8]) -> usize {
+    let start = scalar
+        .iter()
+        .position(|&b| b != 0)
+        .unwrap_or(scalar.len() - 1);
+    let value = &scalar[start..];
+    let pad = usize::from(value[0] & 0x80 != 0);
+    out[0] = DER_INTEGER;
+    out[1] = (pad + value.len()) as u8;
+    out[2] = 0;
+    out[2 + pad..2 + pad + value.len()].copy_from_slice(value);
+    2 + pad + value.len()
+}
+
+/// Converts a DER `ECDSA-Sig-Value` into a raw `r || s` signature.
+///
+/// # Parameters
+///
+/// - `der`: The DER signature, with nothing after it.
+/// - `raw`: Buffer receiving `r || s`, at least [`raw_signature_size`] bytes.
+///
+/// # Returns
+///
+/// The number of bytes written to `raw`.
+pub fn der_to_raw<C: EcdsaCurve>(der: &[u8], raw: &mut [u8]) -> Result<usize, Error> {
+    let raw = raw
+        .get_mut(..raw_signature_size::<C>())
+        .ok_or(Error::BufferTooSmall)?;
+    let (sequence, trailing) = read_tlv(der, DER_SEQUENCE)?;
+    if !trailing.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    let (r, s) = raw.split_at_mut(C::SCALAR_SIZE);
+    let rest = read_integer::<C>(sequence, r)?;
+    let rest = read_integer::<C>(rest, s)?;
+    if !rest.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    Ok(raw.len())
+}
+
+/// Converts a raw `r || s` signature into a DER `ECDSA-Sig-Value`.
+///
+/// # Parameters
+///
+/// - `raw`: The signature, exactly [`raw_signature_size`] bytes.
+/// - `der`: Buffer receiving the DER signature. [`max_der_signature_size`]
+///   bytes are always enough.
+///
+/// # Returns
+///
+/// The number of bytes written to `der`.
+pub fn raw_to_der<C: EcdsaCurve>(raw: &[u8], der: &mut [u8]) -> Result<usize, Error> {
+    const { assert!(C::SCALAR_SIZE <= MAX_SCALAR_SIZE) };
+    if raw.len() != raw_signature_size::<C>() {
+        return Err(Error::InvalidLength);
+    }
+    let (r, s) = raw.split_at(C::SCALAR_SIZE);
+    check_scalar::<C>(r)?;
+    check_scalar::<C>(s)?;
+
+    let mut content = [0u8; 2 * (2 + MAX_SCALAR_SIZE + 1)];
+    let r_len = write_integer(r, &mut content);
+    let s_len = write_integer(s, &mut content[r_len..]);
+    let content = &content[..r_len + s_len];
+
+    let header = if content.len() < 0x80 { 2 } else { 3 };
+    let der = der
+        .get_mut(..header + content.len())
+        .ok_or(Error::BufferTooSmall)?;
+    der[0] = DER_SEQUENCE;
+    if header == 3 {
+        der[1] = 0x81;
+    }
+    der[header - 1] = content.len() as u8;
+    der[header..].copy_from_slice(content);
+    Ok(der.len())
+}
+
+/// SEC1 encoding of a public key type.
+pub trait Sec1Encoding: Sized {
+    type Curve: EcdsaCurve;
+
+    /// Encodes the public key.
+    ///
+    /// # Parameters
+    ///
+    /// - `compress`: Whether to use the compressed form.
+    /// - `out`: Buffer receiving the encoding.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written.
+    fn to_sec1(&self, compress: bool, out: &mut [u8]) -> Result<usize, Error>;
+
+    /// Decodes a public key, compressed or uncompressed.
+    ///
+    /// Implementations should parse with [`Sec1Point::parse`] and must reject
+    /// points that are not on the curve.
+    fn from_sec1(bytes: &[u8]) -> Result<Self, Error>;
+}
+
+/// Fixed-width `r || s` encoding of a signature type.
+pub trait RawSignatureEncoding: Sized {
+    type Curve: EcdsaCurve;
+
+    /// Encodes the signature as `r || s`, [`raw_signature_size`] bytes.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written.
+    fn to_raw(&self, out: &mut [u8]) -> Result<usize, Error>;
+
+    /// Decodes an `r || s` signature, rejecting components outside `[1, n - 1]`.
+    fn from_raw(bytes: &[u8]) -> Result<Self, Error>;
+}
+
+/// DER `ECDSA-Sig-Value` encoding of a signature type.
+///
+/// Implemented for every [`RawSignatureEncoding`] type by converting through
+/// [`raw_to_der`] and [`der_to_raw`].
+pub trait DerSignatureEncoding: Sized {
+    /// Encodes the signature in DER.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written.
+    fn to_der(&self, out: &mut [u8]) -> Result<usize, Error>;
+
+    /// Decodes a DER signature, rejecting any encoding that is not minimal.
+    fn from_der(bytes: &[u8]) -> Result<Self, Error>;
+}
+
+impl<T: RawSignatureEncoding> DerSignatureEncoding for T {
+    fn to_der(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let mut raw = [0u8; 2 * MAX_SCALAR_SIZE];
+        let len = self.to_raw(&mut raw)?;
+        raw_to_der::<T::Curve>(&raw[..len], out)
+    }
+
+    fn from_der(bytes: &[u8]) -> Result<Self, Error> {
+        let mut raw = [0u8; 2 * MAX_SCALAR_SIZE];
+        let len = der_to_raw::<T::Curve>(bytes, &mut raw)?;
+        T::from_raw(&raw[..len])
+    }
+}
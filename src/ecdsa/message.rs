@@ -0,0 +1,150 @@
+//! Signing and verification of complete messages.
+//!
+//! The helpers hash the message with a [`Digest`] implementation and pass the
+//! result to the backend together with the digest's algorithm as the
+//! [`HashMarker`], so the hash and the marker cannot disagree.
+//!
+//! A hash weaker than the curve, such as SHA-256 with P-384, is rejected at
+//! compile time. See [`hash_matches_curve`].
+
+use super::{EcdsaCurve, EcdsaSign, EcdsaVerify, ErrorKind, HashMarker};
+use crate::digest::{self, Digest};
+use crate::key::Validated;
+
+/// Error returned by the message helpers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error<DigestError, EcdsaError> {
+    /// Hashing the message failed
+    Digest(DigestError),
+    /// The signing or verification backend failed
+    Ecdsa(EcdsaError),
+}
+
+impl<DE: digest::Error, EE: super::Error> super::Error for Error<DE, EE> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Digest(e) if e.kind() == digest::ErrorKind::Busy => ErrorKind::Busy,
+            Error::Digest(_) => ErrorKind::Other,
+            Error::Ecdsa(e) => e.kind(),
+        }
+    }
+}
+
+/// Whether the collision resistance of `H` is at least the security strength
+/// of `C`.
+///
+/// Hashes longer than the curve order are truncated by ECDSA and are accepted.
+pub const fn hash_matches_curve<H: HashMarker, C: EcdsaCurve>() -> bool {
+    H::SECURITY_BITS >= C::SECURITY_BITS
+}
+
+/// Finalizes a running digest computation and signs the result.
+///
+/// Lets a message that is not available in one piece be streamed through
+/// `digest` first. Fails to compile if the hash is weaker than the curve.
+///
+/// # Parameters
+/// - `signer`: The signing implementation.
+/// - `curve`: The elliptic curve to use for signing.
+/// - `private_key`: The private key to use for signing.
+/// - `digest`: The digest computation holding the message.
+///
+/// # Returns
+/// A result containing the generated signature, or an error.
+pub fn sign_digest<S: EcdsaSign, D: Digest>(
+    signer: &mut S,
+    curve: &S::Curve,
+    private_key: &S::PrivateKey,
+    digest: &mut D,
+) -> Result<S::Signature, Error<D::Error, S::Error>> {
+    const {
+        assert!(
+            hash_matches_curve::<D::Algorithm, S::Curve>(),
+            "the hash is weaker than the curve"
+        )
+    };
+    let message_hash = digest.finalize().map_err(Error::Digest)?;
+    signer
+        .sign::<D::Algorithm>(curve, private_key, message_hash)
+        .map_err(Error::Ecdsa)
+}
+
+/// Hashes a message and signs the result.
+///
+/// # Parameters
+/// - `signer`: The signing implementation.
+/// - `curve`: The elliptic curve to use for signing.
+/// - `private_key`: The private key to use for signing.
+/// - `digest`: The digest computation to hash with. It is reset first.
+/// - `message`: The message to sign.
+///
+/// # Returns
+/// A result containing the generated signature, or an error.
+pub fn sign_message<S: EcdsaSign, D: Digest>(
+    signer: &mut S,
+    curve: &S::Curve,
+    private_key: &S::PrivateKey,
+    digest: &mut D,
+    message: impl AsRef<[u8]>,
+) -> Result<S::Signature, Error<D::Error, S::Error>> {
+    digest.reset().map_err(Error::Digest)?;
+    digest.update(message.as_ref()).map_err(Error::Digest)?;
+    sign_digest(signer, curve, private_key, digest)
+}
+
+/// Finalizes a running digest computation and verifies a signature over the result.
+///
+/// Fails to compile if the hash is weaker than the curve.
+///
+/// # Parameters
+/// - `verifier`: The verification implementation.
+/// - `curve`: The elliptic curve to use for verification.
+/// - `public_key`: The validated public key to use for verification.
+/// - `digest`: The digest computation holding the message.
+/// - `signature`: The signature to verify.
+///
+/// # Returns
+/// A result indicating whether the signature is valid, or an error.
+pub fn verify_digest<V: EcdsaVerify, D: Digest>(
+    verifier: &mut V,
+    curve: &V::Curve,
+    public_key: &Validated<V::PublicKey>,
+    digest: &mut D,
+    signature: &V::Signature,
+) -> Result<(), Error<D::Error, V::Error>> {
+    const {
+        assert!(
+            hash_matches_curve::<D::Algorithm, V::Curve>(),
+            "the hash is weaker than the curve"
+        )
+    };
+    let message_hash = digest.finalize().map_err(Error::Digest)?;
+    verifier
+        .verify::<D::Algorithm>(curve, public_key, message_hash, signature)
+        .map_err(Error::Ecdsa)
+}
+
+/// Hashes a message and verifies a signature over the result.
+///
+/// # Parameters
+/// - `verifier`: The verification implementation.
+/// - `curve`: The elliptic curve to use for verification.
+/// - `public_key`: The validated public key to use for verification.
+/// - `digest`: The digest computation to hash with. It is reset first.
+/// - `message`: The signed message.
+/// - `signature`: The signature to verify.
+///
+/// # Returns
+/// A result indicating whether the signature is valid, or an error.
+pub fn verify_message<V: EcdsaVerify, D: Digest>(
+    verifier: &mut V,
+    curve: &V::Curve,
+    public_key: &Validated<V::PublicKey>,
+    digest: &mut D,
+    message: impl AsRef<[u8]>,
+    signature: &V::Signature,
+) -> Result<(), Error<D::Error, V::Error>> {
+    digest.reset().map_err(Error::Digest)?;
+    digest.update(message.as_ref()).map_err(Error::Digest)?;
+    verify_digest(verifier, curve, public_key, digest, signature)
+}
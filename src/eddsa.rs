@@ -0,0 +1,227 @@
+//! Edwards-curve digital signatures (EdDSA, RFC 8032).
+//!
+//! Both the pure and the prehash (`ph`) variants are covered. For the prehash
+//! variants the caller hashes the message, for instance with a
+//! [`Digest`](crate::digest::Digest) engine, and passes `PH(M)` to the signer:
+//! SHA-512 for Ed25519ph and the 64-byte SHAKE256 output for Ed448ph.
+
+pub trait Error: core::fmt::Debug {
+    /// Convert error to a generic error kind
+    ///
+    /// By using this method, errors freely defined by HAL implementations
+    /// can be converted to a set of generic errors upon which generic
+    /// code can act.
+    fn kind(&self) -> ErrorKind;
+}
+
+pub trait ErrorType {
+    /// Error type.
+    type Error: Error;
+}
+
+/// Error kind.
+///
+/// This represents a common set of EdDSA errors. Implementations are
+/// free to define more specific or additional error types. However, by providing
+/// a mapping to these common errors, generic code can still react to them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    Busy,
+    InvalidSignature,
+    KeyGenError,
+    SigningError,
+    /// The context is longer than [`MAX_CONTEXT_SIZE`], or not supported by
+    /// the backend for this variant.
+    InvalidContext,
+    /// The prehash is not [`EddsaCurve::PREHASH_SIZE`] bytes long.
+    InvalidPrehashSize,
+    Other,
+}
+
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+/// Largest context accepted by EdDSA, whose length is encoded in one byte.
+pub const MAX_CONTEXT_SIZE: usize = 255;
+
+/// An Edwards curve used for EdDSA.
+pub trait EddsaCurve {
+    /// Size in bytes of an encoded public key.
+    const PUBLIC_KEY_SIZE: usize;
+
+    /// Size in bytes of a private key, the seed the signing scalar is derived from.
+    const PRIVATE_KEY_SIZE: usize;
+
+    /// Size in bytes of a signature, `R || S`.
+    const SIGNATURE_SIZE: usize;
+
+    /// Size in bytes of the message prehash `PH(M)` of the prehash variant.
+    const PREHASH_SIZE: usize;
+
+    /// Content bytes of the DER encoding of the algorithm's ASN.1 object identifier.
+    const OID: &'static [u8];
+
+    /// Identifier of the algorithm. The shipped curves use their TLS `SignatureScheme` value.
+    fn id() -> u32;
+}
+
+macro_rules! eddsa_curve {
+    ($(#[$meta:meta])* $name:ident, public: $public:expr, private: $private:expr, signature: $signature:expr, oid: $oid:expr, id: $id:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $name;
+
+        impl EddsaCurve for $name {
+            const PUBLIC_KEY_SIZE: usize = $public;
+            const PRIVATE_KEY_SIZE: usize = $private;
+            const SIGNATURE_SIZE: usize = $signature;
+            const PREHASH_SIZE: usize = 64;
+            const OID: &'static [u8] = &$oid;
+
+            fn id() -> u32 {
+                $id
+            }
+        }
+    };
+}
+
+eddsa_curve!(
+    /// Ed25519 over edwards25519, OID 1.3.101.112.
+    Ed25519, public: 32, private: 32, signature: 64,
+    oid: [0x2b, 0x65, 0x70], id: 0x0807
+);
+eddsa_curve!(
+    /// Ed448 over edwards448, OID 1.3.101.113.
+    Ed448, public: 57, private: 57, signature: 114,
+    oid: [0x2b, 0x65, 0x71], id: 0x0808
+);
+
+pub trait EddsaTypes {
+    /// The private key, or a reference to it.
+    ///
+    /// Backends that keep keys in a key vault can use a
+    /// [`SlotId`](crate::key::SlotId) or [`KeyHandle`](crate::key::KeyHandle)
+    /// here, so the key never leaves the vault.
+    type PrivateKey;
+    type PublicKey;
+    type Signature;
+    type Curve: EddsaCurve;
+}
+
+/// Trait for EdDSA key generation.
+pub trait EddsaKeyGen: ErrorType + EddsaTypes {
+    /// Generates an EdDSA key pair.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve to use for key generation.
+    ///
+    /// # Returns
+    /// A result containing the generated private and public keys, or an error.
+    fn generate_key_pair(
+        &mut self,
+        curve: &Self::Curve,
+    ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error>;
+}
+
+/// Trait for EdDSA signing.
+///
+/// The `context` parameter selects the variant: an empty context with
+/// [`sign`](Self::sign) on Ed25519 is plain Ed25519, a non-empty one is
+/// Ed25519ctx. Ed448 and both prehash variants always take a context, which
+/// may be empty.
+pub trait EddsaSign: ErrorType {
+    /// The private key, or a reference to it such as a
+    /// [`KeyHandle`](crate::key::KeyHandle) resolved by the backend.
+    type PrivateKey;
+    type Curve: EddsaCurve;
+    type Signature;
+
+    /// Signs a message with the pure variant.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve to use for signing.
+    /// - `private_key`: The private key to use for signing.
+    /// - `message`: The message to sign.
+    /// - `context`: The context, at most [`MAX_CONTEXT_SIZE`] bytes.
+    ///
+    /// # Returns
+    /// A result containing the generated signature, or an error.
+    fn sign(
+        &mut self,
+        curve: &Self::Curve,
+        private_key: &Self::PrivateKey,
+        message: impl AsRef<[u8]>,
+        context: &[u8],
+    ) -> Result<Self::Signature, Self::Error>;
+
+    /// Signs a message with the prehash variant.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve to use for signing.
+    /// - `private_key`: The private key to use for signing.
+    /// - `prehash`: The message prehash `PH(M)`, [`EddsaCurve::PREHASH_SIZE`] bytes.
+    /// - `context`: The context, at most [`MAX_CONTEXT_SIZE`] bytes.
+    ///
+    /// # Returns
+    /// A result containing the generated signature, or an error.
+    fn sign_prehashed(
+        &mut self,
+        curve: &Self::Curve,
+        private_key: &Self::PrivateKey,
+        prehash: impl AsRef<[u8]>,
+        context: &[u8],
+    ) -> Result<Self::Signature, Self::Error>;
+}
+
+/// Trait for EdDSA verification.
+///
+/// Variants are selected as for [`EddsaSign`].
+pub trait EddsaVerify: ErrorType {
+    type PublicKey;
+    type Curve: EddsaCurve;
+    type Signature;
+
+    /// Verifies a signature made with the pure variant.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve to use for verification.
+    /// - `public_key`: The public key to use for verification.
+    /// - `message`: The signed message.
+    /// - `context`: The context the message was signed with.
+    /// - `signature`: The signature to verify.
+    ///
+    /// # Returns
+    /// A result indicating whether the signature is valid, or an error.
+    fn verify(
+        &mut self,
+        curve: &Self::Curve,
+        public_key: &Self::PublicKey,
+        message: impl AsRef<[u8]>,
+        context: &[u8],
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error>;
+
+    /// Verifies a signature made with the prehash variant.
+    ///
+    /// # Parameters
+    /// - `curve`: The curve to use for verification.
+    /// - `public_key`: The public key to use for verification.
+    /// - `prehash`: The message prehash `PH(M)`, [`EddsaCurve::PREHASH_SIZE`] bytes.
+    /// - `context`: The context the message was signed with.
+    /// - `signature`: The signature to verify.
+    ///
+    /// # Returns
+    /// A result indicating whether the signature is valid, or an error.
+    fn verify_prehashed(
+        &mut self,
+        curve: &Self::Curve,
+        public_key: &Self::PublicKey,
+        prehash: impl AsRef<[u8]>,
+        context: &[u8],
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error>;
+}
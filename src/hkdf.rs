@@ -0,0 +1,146 @@
+//! HKDF (RFC 5869) over any [`Mac`] implementation.
+//!
+//! HKDF is specified with HMAC, but the construction only relies on the MAC
+//! interface, so hardware HMAC engines and [`HmacContext`](crate::hmac::HmacContext)
+//! work alike. The pseudorandom key can be handed to [`expand`] as a
+//! [`KeyHandle`], so a key extracted into a key vault never leaves it.
+
+use crate::key::KeyHandle;
+use crate::mac::{self, ErrorKind, Mac, MacAlgorithm};
+
+/// Largest MAC output size supported, that of HMAC-SHA-512.
+pub const MAX_HASH_SIZE: usize = 64;
+
+/// Error returned by the HKDF functions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error<MacError> {
+    /// Underlying MAC error
+    Mac(MacError),
+
+    /// The requested output is longer than 255 MAC outputs
+    OutputTooLong,
+    /// The buffer for the pseudorandom key is shorter than the MAC output
+    BufferTooSmall,
+}
+
+impl<E: mac::Error> mac::Error for Error<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Mac(e) => e.kind(),
+            Error::OutputTooLong => ErrorKind::InvalidOutputSize,
+            Error::BufferTooSmall => ErrorKind::InvalidOutputSize,
+        }
+    }
+}
+
+/// Size in bytes of the MAC output, `HashLen` in RFC 5869.
+const fn hash_len<M: Mac>() -> usize {
+    assert!(<M::Algorithm as MacAlgorithm>::TAG_SIZE <= MAX_HASH_SIZE);
+    <M::Algorithm as MacAlgorithm>::TAG_SIZE
+}
+
+/// Longest output [`expand`] can produce with the MAC `M`.
+pub const fn max_output_size<M: Mac>() -> usize {
+    255 * hash_len::<M>()
+}
+
+/// HKDF-Extract: concentrate the input keying material into a pseudorandom key.
+///
+/// # Parameters
+///
+/// - `mac`: The MAC computation to use. Its key is replaced.
+/// - `salt`: Optional salt. Without one, a string of `HashLen` zero bytes is used.
+/// - `ikm`: The input keying material.
+/// - `prk`: Buffer for the pseudorandom key, at least `HashLen` bytes long.
+///
+/// # Returns
+///
+/// The length of the pseudorandom key written to the start of `prk`.
+pub fn extract<M: Mac>(
+    mac: &mut M,
+    salt: Option<&[u8]>,
+    ikm: &[u8],
+    prk: &mut [u8],
+) -> Result<usize, Error<M::Error>> {
+    let len = const { hash_len::<M>() };
+    if prk.len() < len {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let zeros = [0u8; MAX_HASH_SIZE];
+    let salt = salt.unwrap_or(&zeros[..len]);
+    mac.set_key(KeyHandle::Raw(salt)).map_err(Error::Mac)?;
+    mac.update(ikm).map_err(Error::Mac)?;
+    mac.finalize(&mut prk[..len]).map_err(Error::Mac)?;
+    Ok(len)
+}
+
+/// HKDF-Expand: derive output keying material from a pseudorandom key.
+///
+/// # Parameters
+///
+/// - `mac`: The MAC computation to use. Its key is replaced.
+/// - `prk`: The pseudorandom key, either in memory or held by the MAC backend.
+/// - `info`: Context and application specific information.
+/// - `okm`: Buffer filled with the output keying material, at most
+///   [`max_output_size`] bytes long.
+///
+/// # Returns
+///
+/// A `Result` indicating success or failure. On success, returns `Ok(())`.
+pub fn expand<M: Mac>(
+    mac: &mut M,
+    prk: KeyHandle<'_>,
+    info: &[u8],
+    okm: &mut [u8],
+) -> Result<(), Error<M::Error>> {
+    let len = const { hash_len::<M>() };
+    if okm.len() > max_output_size::<M>() {
+        return Err(Error::OutputTooLong);
+    }
+    mac.set_key(prk).map_err(Error::Mac)?;
+
+    // T(i) = MAC(PRK, T(i - 1) || info || i), with T(0) empty.
+    let mut block = [0u8; MAX_HASH_SIZE];
+    for (i, chunk) in okm.chunks_mut(len).enumerate() {
+        let previous = if i == 0 { &[][..] } else { &block[..len] };
+        let counter = [i as u8 + 1];
+        mac.update_vectored(&[previous, info, &counter])
+            .map_err(Error::Mac)?;
+        mac.finalize(&mut block[..len]).map_err(Error::Mac)?;
+        chunk.copy_from_slice(&block[..chunk.len()]);
+    }
+    block.fill(0);
+    Ok(())
+}
+
+/// Extract-then-expand in one call, without exposing the pseudorandom key.
+///
+/// # Parameters
+///
+/// - `mac`: The MAC computation to use. Its key is replaced.
+/// - `salt`: Optional salt. Without one, a string of `HashLen` zero bytes is used.
+/// - `ikm`: The input keying material.
+/// - `info`: Context and application specific information.
+/// - `okm`: Buffer filled with the output keying material, at most
+///   [`max_output_size`] bytes long.
+///
+/// # Returns
+///
+/// A `Result` indicating success or failure. On success, returns `Ok(())`.
+pub fn derive<M: Mac>(
+    mac: &mut M,
+    salt: Option<&[u8]>,
+    ikm: &[u8],
+    info: &[u8],
+    okm: &mut [u8],
+) -> Result<(), Error<M::Error>> {
+    if okm.len() > max_output_size::<M>() {
+        return Err(Error::OutputTooLong);
+    }
+    let mut prk = [0u8; MAX_HASH_SIZE];
+    let len = extract(mac, salt, ikm, &mut prk)?;
+    let result = expand(mac, KeyHandle::Raw(&prk[..len]), info, okm);
+    prk.fill(0);
+    result
+}
@@ -0,0 +1,250 @@
+//! HMAC (RFC 2104, FIPS 198-1) built on any [`Digest`] implementation.
+//!
+//! Many hash engines have no keyed mode. [`HmacContext`] turns such an engine
+//! into a [`Mac`]: keys longer than the block size are hashed first, and the
+//! inner and outer hashes are computed with the `ipad` and `opad` keys on the
+//! same digest context.
+
+use crate::digest::{self, Digest, DigestAlgorithm, DigestInit};
+use crate::key::KeyHandle;
+use crate::mac::{self, ErrorKind, Hmac, Mac, MacAlgorithm, MacInit};
+
+/// Largest block size of the digest algorithms defined in [`digest`], that of SHA3-224.
+pub const MAX_BLOCK_SIZE: usize = 144;
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+/// Error returned by [`HmacContext`] operations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error<DigestError> {
+    /// Underlying digest error
+    Digest(DigestError),
+
+    /// No key has been set
+    NotInitialized,
+    /// The key handle does not refer to key bytes in memory
+    UnsupportedKeyHandle,
+    /// The output buffer is shorter than the digest
+    InvalidOutputSize,
+    /// The tag does not match the computed MAC
+    VerificationFailed,
+    /// The tag is shorter than the minimum tag size
+    TagTooShort,
+}
+
+impl<E: digest::Error> mac::Error for Error<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Digest(e) => e.kind().into(),
+            Error::NotInitialized => ErrorKind::NotInitialized,
+            Error::UnsupportedKeyHandle => ErrorKind::UnsupportedKeyHandle,
+            Error::InvalidOutputSize => ErrorKind::InvalidOutputSize,
+            Error::VerificationFailed => ErrorKind::VerificationFailed,
+            Error::TagTooShort => ErrorKind::TagTooShort,
+        }
+    }
+}
+
+/// The key padded or hashed to the block size, `K0` in FIPS 198-1.
+///
+/// Kept in its own type so that it is cleared on drop while
+/// [`HmacContext::into_inner`] can still move the digest out.
+struct KeyBlock([u8; MAX_BLOCK_SIZE]);
+
+impl Drop for KeyBlock {
+    fn drop(&mut self) {
+        self.0.fill(0);
+    }
+}
+
+/// An HMAC computation on top of the digest `D`.
+///
+/// A key must be set with [`Mac::set_key`] before any data is authenticated.
+/// The key is processed in software, so only [`KeyHandle::Raw`] keys are
+/// accepted.
+/// Finalizing the computation starts a new message with the same key. The
+/// processed key is cleared when the context is dropped.
+pub struct HmacContext<D: Digest> {
+    digest: D,
+    key_block: KeyBlock,
+    key_set: bool,
+}
+
+impl<D: Digest> HmacContext<D> {
+    const BLOCK_SIZE: usize = {
+        assert!(<D::Algorithm as DigestAlgorithm>::BLOCK_SIZE <= MAX_BLOCK_SIZE);
+        assert!(
+            <D::Algorithm as DigestAlgorithm>::OUTPUT_SIZE
+                <= <D::Algorithm as DigestAlgorithm>::BLOCK_SIZE
+        );
+        <D::Algorithm as DigestAlgorithm>::BLOCK_SIZE
+    };
+
+    /// Create an HMAC computation on an idle digest context.
+    ///
+    /// The digest is reset when the key is set, so any data it already
+    /// processed is discarded.
+    pub fn new(digest: D) -> Self {
+        Self {
+            digest,
+            key_block: KeyBlock([0; MAX_BLOCK_SIZE]),
+            key_set: false,
+        }
+    }
+
+    /// Returns the wrapped digest context.
+    pub fn into_inner(self) -> D {
+        self.digest
+    }
+
+    /// Restart the inner hash with `K0 ^ ipad`.
+    fn start_inner(&mut self) -> Result<(), Error<D::Error>> {
+        let mut pad = [0u8; MAX_BLOCK_SIZE];
+        for (pad, key) in pad.iter_mut().zip(&self.key_block.0) {
+            *pad = key ^ IPAD;
+        }
+        let result = self
+            .digest
+            .reset()
+            .and_then(|()| self.digest.update(&pad[..Self::BLOCK_SIZE]))
+            .map_err(Error::Digest);
+        pad.fill(0);
+        result
+    }
+
+    /// Finish the inner hash and compute the outer hash over it.
+    fn compute(
+        &mut self,
+    ) -> Result<<D::Algorithm as DigestAlgorithm>::DigestOutput, Error<D::Error>> {
+        if !self.key_set {
+            return Err(Error::NotInitialized);
+        }
+        let inner = self.digest.finalize().map_err(Error::Digest)?;
+
+        let mut pad = [0u8; MAX_BLOCK_SIZE];
+        for (pad, key) in pad.iter_mut().zip(&self.key_block.0) {
+            *pad = key ^ OPAD;
+        }
+        let result = self
+            .digest
+            .reset()
+            .and_then(|()| {
+                self.digest
+                    .update_vectored(&[&pad[..Self::BLOCK_SIZE], inner.as_ref()])
+            })
+            .map_err(Error::Digest);
+        pad.fill(0);
+        result?;
+        let tag = self.digest.finalize().map_err(Error::Digest)?;
+
+        self.start_inner()?;
+        Ok(tag)
+    }
+}
+
+impl<D: Digest> mac::ErrorType for HmacContext<D> {
+    type Error = Error<D::Error>;
+}
+
+impl<D: Digest> Mac for HmacContext<D> {
+    type Algorithm = Hmac<D::Algorithm>;
+
+    fn set_key(&mut self, key: KeyHandle<'_>) -> Result<(), Self::Error> {
+        let key = key.as_raw().ok_or(Error::UnsupportedKeyHandle)?;
+        self.key_block.0.fill(0);
+        self.key_set = false;
+        if key.len() > Self::BLOCK_SIZE {
+            self.digest.reset().map_err(Error::Digest)?;
+            self.digest.update(key).map_err(Error::Digest)?;
+            let hashed = self.digest.finalize().map_err(Error::Digest)?;
+            let hashed = hashed.as_ref();
+            self.key_block.0[..hashed.len()].copy_from_slice(hashed);
+        } else {
+            self.key_block.0[..key.len()].copy_from_slice(key);
+        }
+        self.start_inner()?;
+        self.key_set = true;
+        Ok(())
+    }
+
+    fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+        if !self.key_set {
+            return Err(Error::NotInitialized);
+        }
+        self.digest.update(input).map_err(Error::Digest)
+    }
+
+    fn update_vectored(&mut self, inputs: &[&[u8]]) -> Result<(), Self::Error> {
+        if !self.key_set {
+            return Err(Error::NotInitialized);
+        }
+        self.digest.update_vectored(inputs).map_err(Error::Digest)
+    }
+
+    fn reset(&mut self) -> Result<(), Self::Error> {
+        if !self.key_set {
+            return Err(Error::NotInitialized);
+        }
+        self.start_inner()
+    }
+
+    fn finalize(&mut self, out: &mut [u8]) -> Result<(), Self::Error> {
+        let size = <D::Algorithm as DigestAlgorithm>::OUTPUT_SIZE;
+        if out.len() < size {
+            return Err(Error::InvalidOutputSize);
+        }
+        let tag = self.compute()?;
+        out[..size].copy_from_slice(tag.as_ref());
+        Ok(())
+    }
+
+    fn verify(&mut self, tag: &[u8]) -> Result<(), Self::Error> {
+        let expected = self.compute()?;
+        mac::verify_tag(expected.as_ref(), tag, Hmac::<D::Algorithm>::MIN_TAG_SIZE).map_err(
+            |kind| match kind {
+                ErrorKind::TagTooShort => Error::TagTooShort,
+                _ => Error::VerificationFailed,
+            },
+        )
+    }
+}
+
+/// A MAC engine computing HMAC with the digest algorithms of the hash engine `E`.
+///
+/// Each call to [`MacInit::init`] starts a digest computation on the hash
+/// engine and wraps it in an [`HmacContext`].
+pub struct HmacEngine<E> {
+    engine: E,
+}
+
+impl<E> HmacEngine<E> {
+    pub fn new(engine: E) -> Self {
+        Self { engine }
+    }
+
+    /// Returns the wrapped hash engine.
+    pub fn into_inner(self) -> E {
+        self.engine
+    }
+}
+
+impl<E: digest::ErrorType> mac::ErrorType for HmacEngine<E> {
+    type Error = Error<E::Error>;
+}
+
+impl<E, A> MacInit<Hmac<A>> for HmacEngine<E>
+where
+    E: DigestInit<A>,
+    A: DigestAlgorithm,
+{
+    type OpContext<'a>
+        = HmacContext<E::OpContext<'a>>
+    where
+        Self: 'a;
+
+    fn init(&mut self, init_params: Hmac<A>) -> Result<Self::OpContext<'_>, Self::Error> {
+        let digest = self.engine.init(init_params.0).map_err(Error::Digest)?;
+        Ok(HmacContext::new(digest))
+    }
+}
@@ -0,0 +1,272 @@
+//! Key-based key derivation (NIST SP 800-108) over any [`Mac`] implementation.
+//!
+//! Supports the counter, feedback and double-pipeline iteration modes. The
+//! key derivation key is passed as a [`KeyHandle`], so the derivation can run
+//! on a key that never leaves the MAC backend.
+
+use crate::key::KeyHandle;
+use crate::mac::{self, ErrorKind, Mac, MacAlgorithm};
+
+/// Largest MAC output size supported, that of HMAC-SHA-512.
+pub const MAX_PRF_SIZE: usize = 64;
+
+/// Error returned by [`derive`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error<MacError> {
+    /// Underlying MAC error
+    Mac(MacError),
+
+    /// The requested output needs more blocks than the counter can count,
+    /// or its length in bits does not fit the 32-bit `[L]` field
+    OutputTooLong,
+    /// The counter location is not possible with the given fixed input
+    InvalidCounterLocation,
+    /// The feedback-mode IV is longer than [`MAX_PRF_SIZE`]
+    InvalidIv,
+}
+
+impl<E: mac::Error> mac::Error for Error<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Mac(e) => e.kind(),
+            Error::OutputTooLong => ErrorKind::InvalidOutputSize,
+            Error::InvalidCounterLocation => ErrorKind::InvalidInputLength,
+            Error::InvalidIv => ErrorKind::InvalidInputLength,
+        }
+    }
+}
+
+/// Width of the block counter `[i]`, `r` in SP 800-108.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CounterWidth {
+    Bits8,
+    Bits16,
+    Bits24,
+    Bits32,
+}
+
+impl CounterWidth {
+    fn bytes(self) -> usize {
+        match self {
+            CounterWidth::Bits8 => 1,
+            CounterWidth::Bits16 => 2,
+            CounterWidth::Bits24 => 3,
+            CounterWidth::Bits32 => 4,
+        }
+    }
+}
+
+/// Position of the block counter in the PRF input.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CounterLocation {
+    /// Before the chaining value. In counter mode, which has no chaining
+    /// value, this is the same as [`BeforeFixed`](Self::BeforeFixed).
+    BeforeIteration,
+    /// Between the chaining value and the fixed input data, as in SP 800-108.
+    BeforeFixed,
+    /// After the fixed input data.
+    AfterFixed,
+    /// At the given byte offset inside the fixed input data. Only possible
+    /// with [`FixedInput::Raw`].
+    MiddleFixed(usize),
+}
+
+/// The block counter `[i]` of the PRF input.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Counter {
+    pub width: CounterWidth,
+    pub location: CounterLocation,
+}
+
+impl Counter {
+    pub const fn new(width: CounterWidth, location: CounterLocation) -> Self {
+        Self { width, location }
+    }
+}
+
+/// The iteration mode of the KDF.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Mode<'a> {
+    /// `K(i) = PRF(KI, [i] || FixedInput)`.
+    Counter(Counter),
+    /// `K(i) = PRF(KI, K(i-1) || [i] || FixedInput)`, with `K(0) = iv`. The
+    /// counter is optional.
+    Feedback {
+        iv: &'a [u8],
+        counter: Option<Counter>,
+    },
+    /// `A(i) = PRF(KI, A(i-1))` with `A(0) = FixedInput`, and
+    /// `K(i) = PRF(KI, A(i) || [i] || FixedInput)`. The counter is optional.
+    DoublePipeline { counter: Option<Counter> },
+}
+
+/// The fixed input data of the PRF.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FixedInput<'a> {
+    /// Fixed input data formatted by the caller.
+    Raw(&'a [u8]),
+    /// `Label || 0x00 || Context || [L]`, the encoding suggested by SP 800-108,
+    /// with `[L]` the output length in bits as a big-endian `u32`.
+    LabelContext { label: &'a [u8], context: &'a [u8] },
+}
+
+/// Up to seven slices forming one PRF input, passed to [`Mac::update_vectored`].
+struct Pieces<'b> {
+    slices: [&'b [u8]; 7],
+    len: usize,
+}
+
+impl<'b> Pieces<'b> {
+    fn new() -> Self {
+        Self {
+            slices: [&[]; 7],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, slice: &'b [u8]) {
+        self.slices[self.len] = slice;
+        self.len += 1;
+    }
+
+    fn extend(&mut self, slices: &[&'b [u8]]) {
+        for slice in slices {
+            self.push(slice);
+        }
+    }
+
+    fn as_slice(&self) -> &[&'b [u8]] {
+        &self.slices[..self.len]
+    }
+}
+
+/// Derive keying material with the SP 800-108 KDF.
+///
+/// # Parameters
+///
+/// - `mac`: The MAC computation to use as the PRF. Its key is replaced.
+/// - `key`: The key derivation key `KI`, either in memory or held by the MAC backend.
+/// - `mode`: The iteration mode and counter format.
+/// - `fixed_input`: The fixed input data.
+/// - `out`: Buffer filled with the derived keying material. Its length determines `L`.
+///
+/// # Returns
+///
+/// A `Result` indicating success or failure. On success, returns `Ok(())`.
+pub fn derive<M: Mac>(
+    mac: &mut M,
+    key: KeyHandle<'_>,
+    mode: Mode<'_>,
+    fixed_input: FixedInput<'_>,
+    out: &mut [u8],
+) -> Result<(), Error<M::Error>> {
+    let h = const {
+        assert!(<M::Algorithm as MacAlgorithm>::TAG_SIZE <= MAX_PRF_SIZE);
+        <M::Algorithm as MacAlgorithm>::TAG_SIZE
+    };
+
+    let output_bits = u32::try_from(out.len())
+        .ok()
+        .and_then(|len| len.checked_mul(8))
+        .ok_or(Error::OutputTooLong)?;
+    let blocks = out.len().div_ceil(h);
+
+    let counter = match mode {
+        Mode::Counter(counter) => Some(counter),
+        Mode::Feedback { counter, .. } | Mode::DoublePipeline { counter } => counter,
+    };
+    if let Some(counter) = counter {
+        let max_blocks = (1u64 << (8 * counter.width.bytes())) - 1;
+        if blocks as u64 > max_blocks {
+            return Err(Error::OutputTooLong);
+        }
+    }
+
+    let encoded_length = output_bits.to_be_bytes();
+    let fixed: [&[u8]; 4] = match fixed_input {
+        FixedInput::Raw(data) => [data, &[], &[], &[]],
+        FixedInput::LabelContext { label, context } => [label, &[0], context, &encoded_length],
+    };
+    if let Some(Counter {
+        location: CounterLocation::MiddleFixed(offset),
+        ..
+    }) = counter
+    {
+        match fixed_input {
+            FixedInput::Raw(data) if offset <= data.len() => {}
+            _ => return Err(Error::InvalidCounterLocation),
+        }
+    }
+
+    // K(i - 1) in feedback mode, A(i) in double-pipeline mode.
+    let mut chain = [0u8; MAX_PRF_SIZE];
+    let mut chain_len = 0;
+    if let Mode::Feedback { iv, .. } = mode {
+        if iv.len() > MAX_PRF_SIZE {
+            return Err(Error::InvalidIv);
+        }
+        chain[..iv.len()].copy_from_slice(iv);
+        chain_len = iv.len();
+    }
+
+    mac.set_key(key).map_err(Error::Mac)?;
+
+    let mut block = [0u8; MAX_PRF_SIZE];
+    for (i, chunk) in out.chunks_mut(h).enumerate() {
+        if let Mode::DoublePipeline { .. } = mode {
+            if i == 0 {
+                mac.update_vectored(&fixed)
+            } else {
+                mac.update(&chain[..h])
+            }
+            .map_err(Error::Mac)?;
+            mac.finalize(&mut block[..h]).map_err(Error::Mac)?;
+            chain[..h].copy_from_slice(&block[..h]);
+            chain_len = h;
+        }
+
+        let encoded_counter = (i as u32 + 1).to_be_bytes();
+        let mut pieces = Pieces::new();
+        match counter {
+            None => {
+                pieces.push(&chain[..chain_len]);
+                pieces.extend(&fixed);
+            }
+            Some(counter) => {
+                let encoded_counter = &encoded_counter[4 - counter.width.bytes()..];
+                match counter.location {
+                    CounterLocation::BeforeIteration => {
+                        pieces.push(encoded_counter);
+                        pieces.push(&chain[..chain_len]);
+                        pieces.extend(&fixed);
+                    }
+                    CounterLocation::BeforeFixed => {
+                        pieces.push(&chain[..chain_len]);
+                        pieces.push(encoded_counter);
+                        pieces.extend(&fixed);
+                    }
+                    CounterLocation::AfterFixed => {
+                        pieces.push(&chain[..chain_len]);
+                        pieces.extend(&fixed);
+                        pieces.push(encoded_counter);
+                    }
+                    CounterLocation::MiddleFixed(offset) => {
+                        let (head, tail) = fixed[0].split_at(offset);
+                        pieces.extend(&[&chain[..chain_len], head, encoded_counter, tail]);
+                    }
+                }
+            }
+        }
+        mac.update_vectored(pieces.as_slice()).map_err(Error::Mac)?;
+        mac.finalize(&mut block[..h]).map_err(Error::Mac)?;
+        chunk.copy_from_slice(&block[..chunk.len()]);
+
+        if let Mode::Feedback { .. } = mode {
+            chain[..h].copy_from_slice(&block[..h]);
+            chain_len = h;
+        }
+    }
+    block.fill(0);
+    chain.fill(0);
+    Ok(())
+}
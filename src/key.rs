@@ -0,0 +1,121 @@
+//! Key handles.
+//!
+//! Operations that need a secret key reference it through a [`KeyHandle`]
+//! instead of taking the key bytes, so that keys kept in a key vault never have
+//! to pass through software. The backend resolves the handle internally.
+//! Software backends only accept [`KeyHandle::Raw`]. Keys generated inside a
+//! key vault carry a [`KeyPolicy`] restricting their use.
+//!
+//! Public keys from outside the device are wrapped in [`Validated`] once they
+//! pass validation.
+
+use core::fmt;
+use core::ops::Deref;
+
+/// Identifier of a key slot in a hardware key vault.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SlotId(pub u32);
+
+/// What a key held in a slot may be used for.
+///
+/// The policy is fixed when the key is generated into its slot. The backend
+/// refuses any use it does not allow, and refuses to copy the key out of the
+/// vault unless [`export`](Self::export) is set.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct KeyPolicy {
+    /// The key may create signatures.
+    pub sign: bool,
+    /// The key may be used for key agreement.
+    pub agree: bool,
+    /// The key may leave the vault.
+    pub export: bool,
+}
+
+impl KeyPolicy {
+    /// Signing only, never exported, as for a device identity key.
+    pub const SIGN_ONLY: Self = Self {
+        sign: true,
+        agree: false,
+        export: false,
+    };
+}
+
+/// A reference to a secret key.
+///
+/// The `Debug` output never includes key material.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum KeyHandle<'a> {
+    /// Key bytes in memory.
+    Raw(&'a [u8]),
+    /// A key held in a slot of the backend's key vault.
+    Slot(SlotId),
+    /// A key blob encrypted under a key known only to the backend, which
+    /// unwraps it internally.
+    Wrapped(&'a [u8]),
+}
+
+impl<'a> KeyHandle<'a> {
+    /// Returns the key bytes of a [`KeyHandle::Raw`] handle.
+    pub fn as_raw(&self) -> Option<&'a [u8]> {
+        match *self {
+            KeyHandle::Raw(key) => Some(key),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Debug for KeyHandle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyHandle::Raw(key) => write!(f, "Raw({} bytes)", key.len()),
+            KeyHandle::Slot(slot) => f.debug_tuple("Slot").field(slot).finish(),
+            KeyHandle::Wrapped(blob) => write!(f, "Wrapped({} bytes)", blob.len()),
+        }
+    }
+}
+
+impl<'a> From<&'a [u8]> for KeyHandle<'a> {
+    fn from(key: &'a [u8]) -> Self {
+        KeyHandle::Raw(key)
+    }
+}
+
+impl<'a, const N: usize> From<&'a [u8; N]> for KeyHandle<'a> {
+    fn from(key: &'a [u8; N]) -> Self {
+        KeyHandle::Raw(key)
+    }
+}
+
+impl From<SlotId> for KeyHandle<'_> {
+    fn from(slot: SlotId) -> Self {
+        KeyHandle::Slot(slot)
+    }
+}
+
+/// A public key that passed full public-key validation.
+///
+/// Values of this type are only created by the provided `validate` methods of
+/// [`EcdsaValidate`](crate::ecdsa::EcdsaValidate) and
+/// [`EcdhValidate`](crate::ecdh::EcdhValidate), so an operation taking a
+/// `&Validated<K>` cannot be handed a key that was never checked.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Validated<K>(K);
+
+impl<K> Validated<K> {
+    pub(crate) fn new(key: K) -> Self {
+        Self(key)
+    }
+
+    /// Returns the validated key.
+    pub fn into_inner(self) -> K {
+        self.0
+    }
+}
+
+impl<K> Deref for Validated<K> {
+    type Target = K;
+
+    fn deref(&self) -> &K {
+        &self.0
+    }
+}
@@ -3,8 +3,21 @@
 
 pub mod rsa;
 pub mod ecdsa;
+pub mod ecdh;
+pub mod eddsa;
 pub mod mac;
+pub mod hmac;
+pub mod hkdf;
+pub mod kbkdf;
+pub mod rfc6979;
 pub mod digest;
+pub mod xof;
+pub mod key;
 
 
-pub mod block_device;
\ No newline at end of file
+pub mod block_device;
+pub mod measurement;
+pub mod sw;
+
+#[cfg(feature = "rustcrypto")]
+pub mod rustcrypto;
@@ -1,43 +1,70 @@
+//! Message authentication codes.
+//!
+//! The MAC algorithms are identified by marker types implementing
+//! [`MacAlgorithm`], which also carry the key and tag sizes each algorithm
+//! accepts. Hash-based ([`Hmac`], [`Kmac128`], [`Kmac256`]) and block-cipher-based
+//! ([`AesCmac`], [`AesGmac`]) MACs share the same [`Mac`] interface.
+
+use crate::digest::{self, DigestAlgorithm};
+use crate::key::KeyHandle;
+
 /// Error kind.
 ///
-/// This represents a common set of digest operation errors. Implementations are
+/// This represents a common set of MAC operation errors. Implementations are
 /// free to define more specific or additional error types. However, by providing
 /// a mapping to these common errors, generic code can still react to them.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[non_exhaustive]
 pub enum ErrorKind {
-    /// The input data length is not valid for the hash function.
+    /// The input data length is not valid for the MAC algorithm.
     InvalidInputLength,
 
-    /// The specified hash algorithm is not supported by the hardware or software implementation.
+    /// The specified MAC algorithm is not supported by the hardware or software implementation.
     UnsupportedAlgorithm,
 
-    /// Failed to allocate memory for the hash computation.
+    /// Failed to allocate memory for the MAC computation.
     MemoryAllocationFailure,
 
-    /// Failed to initialize the hash computation context.
+    /// Failed to initialize the MAC computation context.
     InitializationError,
 
-    /// Error occurred while updating the hash computation with new data.
+    /// Error occurred while updating the MAC computation with new data.
     UpdateError,
 
-    /// Error occurred while finalizing the hash computation.
+    /// Error occurred while finalizing the MAC computation.
     FinalizationError,
 
-    /// The hardware accelerator is busy and cannot process the hash computation.
+    /// The hardware accelerator is busy and cannot process the MAC computation.
     HardwareAcceleratorBusy,
 
-    /// General hardware failure during hash computation.
+    /// General hardware failure during MAC computation.
     HardwareFailure,
 
-    /// The specified output size is not valid for the hash function.
+    /// The specified output size is not valid for the MAC algorithm.
     InvalidOutputSize,
 
-    /// Insufficient permissions to access the hardware or perform the hash computation.
+    /// Insufficient permissions to access the hardware or perform the MAC computation.
     PermissionDenied,
 
-    /// The hash computation context has not been initialized.
+    /// The MAC computation context has not been initialized, or no key has been set.
     NotInitialized,
+
+    /// The computed MAC tag does not match the expected tag.
+    VerificationFailed,
+
+    /// The key length is not accepted by the MAC algorithm.
+    InvalidKeySize,
+
+    /// The kind of key handle is not supported by the implementation, such as
+    /// a key slot passed to a software implementation.
+    UnsupportedKeyHandle,
+
+    /// The key handle does not resolve to a usable key, such as an empty slot
+    /// or a wrapped key that fails to unwrap.
+    InvalidKeyHandle,
+
+    /// The tag to verify is shorter than the minimum tag size of the algorithm.
+    TagTooShort,
 }
 
 pub trait Error: core::fmt::Debug {
@@ -60,46 +87,305 @@ impl Error for core::convert::Infallible {
     }
 }
 
+impl Error for ErrorKind {
+    fn kind(&self) -> ErrorKind {
+        *self
+    }
+}
+
+impl From<digest::ErrorKind> for ErrorKind {
+    /// Map the error of a digest used to build a MAC, such as the hash inside HMAC.
+    fn from(kind: digest::ErrorKind) -> Self {
+        use digest::ErrorKind as Digest;
+
+        match kind {
+            Digest::InvalidInputLength => ErrorKind::InvalidInputLength,
+            Digest::UnsupportedAlgorithm => ErrorKind::UnsupportedAlgorithm,
+            Digest::MemoryAllocationFailure => ErrorKind::MemoryAllocationFailure,
+            Digest::InitializationError => ErrorKind::InitializationError,
+            Digest::UpdateError => ErrorKind::UpdateError,
+            Digest::FinalizationError => ErrorKind::FinalizationError,
+            Digest::Busy => ErrorKind::HardwareAcceleratorBusy,
+            Digest::HardwareFailure => ErrorKind::HardwareFailure,
+            Digest::InvalidOutputSize => ErrorKind::InvalidOutputSize,
+            Digest::PermissionDenied => ErrorKind::PermissionDenied,
+            Digest::NotInitialized => ErrorKind::NotInitialized,
+            Digest::InvalidState => ErrorKind::InitializationError,
+        }
+    }
+}
+
 pub trait ErrorType {
     /// Error type.
     type Error: Error;
 }
 
-/// Message Authentication algorithm
-pub trait Mac: ErrorType {
-    type InitParams;
+/// Default minimum length in bytes of a truncated tag, 96 bits.
+pub const MIN_TAG_SIZE: usize = 12;
+
+/// Compare two byte strings in constant time.
+///
+/// The running time depends only on the lengths of the inputs, not on their
+/// contents. Inputs of different lengths are never equal.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |diff, (a, b)| diff | (a ^ b));
+    core::hint::black_box(diff) == 0
+}
+
+/// Check a received tag against a computed one, accepting truncated tags.
+///
+/// This is the comparison [`Mac::verify`] implementations are expected to
+/// use. A tag matches if it equals the leading bytes of `computed`; the
+/// comparison runs in constant time.
+///
+/// # Parameters
+///
+/// - `computed`: The full-length tag computed over the message.
+/// - `tag`: The tag to verify, possibly truncated.
+/// - `min_tag_size`: The shortest tag accepted, usually [`MacAlgorithm::MIN_TAG_SIZE`].
+///
+/// # Returns
+///
+/// `Ok(())` if the tag matches. [`ErrorKind::TagTooShort`] if `tag` is shorter than
+/// `min_tag_size`, and [`ErrorKind::VerificationFailed`] if it is longer than
+/// `computed` or does not match.
+pub fn verify_tag(computed: &[u8], tag: &[u8], min_tag_size: usize) -> Result<(), ErrorKind> {
+    if tag.len() < min_tag_size {
+        return Err(ErrorKind::TagTooShort);
+    }
+    match computed.get(..tag.len()) {
+        Some(prefix) if ct_eq(prefix, tag) => Ok(()),
+        _ => Err(ErrorKind::VerificationFailed),
+    }
+}
+
+/// Key lengths accepted by a MAC algorithm.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum KeySize {
+    /// Any length from `min` to `max` bytes, inclusive.
+    Range { min: usize, max: usize },
+    /// One of the listed lengths in bytes.
+    OneOf(&'static [usize]),
+}
+
+impl KeySize {
+    /// Returns whether a key of `len` bytes is accepted.
+    pub fn contains(&self, len: usize) -> bool {
+        match *self {
+            KeySize::Range { min, max } => (min..=max).contains(&len),
+            KeySize::OneOf(sizes) => sizes.contains(&len),
+        }
+    }
+}
+
+/// A MAC algorithm.
+///
+/// Implemented by marker types so that the algorithm, and with it the accepted
+/// key sizes and the tag size, is known at compile time. Markers of algorithms
+/// taking per-computation parameters, such as the GMAC IV, carry them as fields.
+pub trait MacAlgorithm {
+    /// Key lengths accepted by the algorithm.
+    const KEY_SIZE: KeySize;
+
+    /// Size of a full-length tag in bytes.
+    const TAG_SIZE: usize;
+
+    /// Size of the shortest truncated tag accepted by [`Mac::verify`].
+    ///
+    /// Tags can be truncated to any length from this size up to
+    /// [`TAG_SIZE`](Self::TAG_SIZE).
+    const MIN_TAG_SIZE: usize = MIN_TAG_SIZE;
+
+    /// Whether the tag length is an input to the computation.
+    ///
+    /// Such algorithms, like KMAC, produce a tag of any requested length, and
+    /// tags of different lengths over the same message are unrelated.
+    /// [`TAG_SIZE`](Self::TAG_SIZE) is then the recommended length.
+    const VARIABLE_TAG_SIZE: bool = false;
+}
+
+/// HMAC (FIPS 198-1) with the digest algorithm `A`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Hmac<A>(pub A);
+
+impl<A: DigestAlgorithm> MacAlgorithm for Hmac<A> {
+    const KEY_SIZE: KeySize = KeySize::Range {
+        min: 0,
+        max: usize::MAX,
+    };
+    const TAG_SIZE: usize = A::OUTPUT_SIZE;
+}
+
+/// HMAC-SHA-224.
+pub type HmacSha2_224 = Hmac<digest::Sha2_224>;
+/// HMAC-SHA-256.
+pub type HmacSha2_256 = Hmac<digest::Sha2_256>;
+/// HMAC-SHA-384.
+pub type HmacSha2_384 = Hmac<digest::Sha2_384>;
+/// HMAC-SHA-512.
+pub type HmacSha2_512 = Hmac<digest::Sha2_512>;
+/// HMAC-SHA3-256.
+pub type HmacSha3_256 = Hmac<digest::Sha3_256>;
+/// HMAC-SHA3-384.
+pub type HmacSha3_384 = Hmac<digest::Sha3_384>;
+/// HMAC-SHA3-512.
+pub type HmacSha3_512 = Hmac<digest::Sha3_512>;
+
+/// AES key lengths in bytes.
+const AES_KEY_SIZES: KeySize = KeySize::OneOf(&[16, 24, 32]);
+
+/// AES-CMAC (NIST SP 800-38B, RFC 4493).
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct AesCmac;
+
+impl MacAlgorithm for AesCmac {
+    const KEY_SIZE: KeySize = AES_KEY_SIZES;
+    const TAG_SIZE: usize = 16;
+}
+
+/// AES-GMAC (NIST SP 800-38D), GCM authenticating data without encrypting any.
+///
+/// An IV must never be used twice with the same key.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct AesGmac<'a> {
+    /// Initialization vector, 12 bytes unless the backend supports other lengths.
+    pub iv: &'a [u8],
+}
+
+impl<'a> AesGmac<'a> {
+    pub const fn new(iv: &'a [u8]) -> Self {
+        Self { iv }
+    }
+}
+
+impl MacAlgorithm for AesGmac<'_> {
+    const KEY_SIZE: KeySize = AES_KEY_SIZES;
+    const TAG_SIZE: usize = 16;
+}
+
+/// KMAC128 (NIST SP 800-185).
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Kmac128<'a> {
+    /// Customization string `S`.
+    pub customization: &'a [u8],
+}
+
+impl<'a> Kmac128<'a> {
+    pub const fn new(customization: &'a [u8]) -> Self {
+        Self { customization }
+    }
+}
+
+impl MacAlgorithm for Kmac128<'_> {
+    const KEY_SIZE: KeySize = KeySize::Range {
+        min: 0,
+        max: usize::MAX,
+    };
+    const TAG_SIZE: usize = 32;
+    const VARIABLE_TAG_SIZE: bool = true;
+}
+
+/// KMAC256 (NIST SP 800-185).
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Kmac256<'a> {
+    /// Customization string `S`.
+    pub customization: &'a [u8],
+}
+
+impl<'a> Kmac256<'a> {
+    pub const fn new(customization: &'a [u8]) -> Self {
+        Self { customization }
+    }
+}
+
+impl MacAlgorithm for Kmac256<'_> {
+    const KEY_SIZE: KeySize = KeySize::Range {
+        min: 0,
+        max: usize::MAX,
+    };
+    const TAG_SIZE: usize = 64;
+    const VARIABLE_TAG_SIZE: bool = true;
+}
+
+/// A MAC engine that can start MAC computations.
+///
+/// Starting a computation borrows the engine exclusively and hands back an
+/// operation context on which the data is authenticated. While the context is
+/// alive the borrow checker rejects any other use of the engine.
+pub trait MacInit<A: MacAlgorithm>: ErrorType {
+    /// The operation context returned by [`MacInit::init`].
+    ///
+    /// Implementations should release any hardware resources held by the
+    /// operation when the context is dropped.
+    type OpContext<'a>: Mac<Algorithm = A> + ErrorType<Error = Self::Error>
+    where
+        Self: 'a;
+
     /// Init instance of the crypto function with the given context.
     ///
     /// # Parameters
     ///
-    /// - `init_params`: The context or configuration parameters for the crypto function.
+    /// - `init_params`: The MAC algorithm to compute, including any per-computation parameters.
     ///
     /// # Returns
     ///
-    /// A new instance of the hash function.    
-    fn init(init_params: Self::InitParams) -> Result<(), Self::Error>;
+    /// An operation context borrowing the engine until it is dropped.
+    fn init(&mut self, init_params: A) -> Result<Self::OpContext<'_>, Self::Error>;
+}
+
+/// Message Authentication algorithm
+pub trait Mac: ErrorType {
+    /// The MAC algorithm computed.
+    type Algorithm: MacAlgorithm;
 
-    /// Sets the key for the HMAC algorithm.
+    /// Sets the key for the MAC algorithm.
+    ///
+    /// Hardware backends resolve slot and wrapped-key handles internally, so
+    /// the key material never passes through software.
     ///
     /// # Parameters
     ///
-    /// - `key`: The key to be used for HMAC.
+    /// - `key`: Handle of the key to be used. Its length must be accepted by [`MacAlgorithm::KEY_SIZE`].
     ///
     /// # Returns
     ///
     /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns an error of type `Self::Error`.
-    fn set_key(&mut self, key: &[u8]) -> Result<(), Self::Error>;
+    fn set_key(&mut self, key: KeyHandle<'_>) -> Result<(), Self::Error>;
 
     /// Update state using provided input data.
     ///
     /// # Parameters
     ///
-    /// - `input`: The input data to be hashed. This can be any type that implements `AsRef<[u8]>`.
+    /// - `input`: The input data to be authenticated. The data is only read, so it may live in read-only memory.
     ///
     /// # Returns
     ///
     /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns a `CryptoError`.    
-    fn update(&mut self, input: &mut [u8]) -> Result<(), Self::Error>;
+    fn update(&mut self, input: &[u8]) -> Result<(), Self::Error>;
+
+    /// Update state using several input slices, in order.
+    ///
+    /// The result is the same as calling [`update`](Self::update) on each slice
+    /// in turn. Implementations backed by a DMA-capable engine should override
+    /// this to process the slices as one scatter-gather list, without copying
+    /// them into a contiguous buffer.
+    ///
+    /// # Parameters
+    ///
+    /// - `inputs`: The input slices to be authenticated.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns a `CryptoError`.
+    fn update_vectored(&mut self, inputs: &[&[u8]]) -> Result<(), Self::Error> {
+        for input in inputs {
+            self.update(input)?;
+        }
+        Ok(())
+    }
 
     /// Reset instance to its initial state.
     ///
@@ -112,7 +398,9 @@ pub trait Mac: ErrorType {
     ///
     /// # Parameters
     ///
-    /// - `out`: A mutable slice to store the hash output. The length of the slice must be at least `MAX_OUTPUT_SIZE`.
+    /// - `out`: A mutable slice to store the tag. The first [`MacAlgorithm::TAG_SIZE`] bytes are
+    ///   written, so the slice must be at least that long. For algorithms with a
+    ///   [variable tag size](MacAlgorithm::VARIABLE_TAG_SIZE), the whole slice is filled instead.
     ///
     /// # Returns
     ///
@@ -121,9 +409,15 @@ pub trait Mac: ErrorType {
 
     /// Verifies if the given MAC tag matches the expected result.
     ///
+    /// The tag may be truncated, in which case it is compared with the leading
+    /// bytes of the computed tag. Tags shorter than [`MacAlgorithm::MIN_TAG_SIZE`]
+    /// are rejected with [`ErrorKind::TagTooShort`]. The comparison must run in
+    /// constant time; implementations should use [`verify_tag`].
+    ///
     /// # Parameters
     ///
-    /// - `tag`: The MAC tag to be verified.
+    /// - `tag`: The MAC tag to be verified. For algorithms with a
+    ///   [variable tag size](MacAlgorithm::VARIABLE_TAG_SIZE), its length selects the tag to compute.
     ///
     /// # Returns
     ///
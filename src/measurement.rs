@@ -0,0 +1,330 @@
+//! Measurement registers for measured boot.
+//!
+//! A measurement register can only be changed by extending it:
+//! `new = H(old || measurement)`. Registers are grouped in banks, one bank per
+//! hash algorithm, and can be locked against further extension until the next
+//! boot. A signed quote over a selection of registers attests their values to
+//! a remote party.
+
+use crate::digest::{self, Digest, DigestAlgorithm};
+use crate::ecdsa::message::hash_matches_curve;
+use crate::ecdsa::{self, EcdsaSign};
+
+/// Error kind.
+///
+/// This represents a common set of measurement register errors, used by the
+/// algorithm-independent [`Bank`] trait.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The register index is out of range.
+    InvalidIndex,
+    /// The register is locked until the next boot.
+    Locked,
+    /// The register can only be reset on boot.
+    NotResettable,
+    /// The register selection is empty, unsorted or contains duplicates.
+    InvalidSelection,
+    /// The digest implementation failed.
+    Digest(digest::ErrorKind),
+    /// The signing implementation failed.
+    Sign(ecdsa::ErrorKind),
+}
+
+/// Error returned by [`MeasurementBank`] operations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error<DigestError> {
+    /// Underlying digest error
+    Digest(DigestError),
+
+    /// The register index is out of range
+    InvalidIndex,
+    /// The register is locked until the next boot
+    Locked,
+    /// The register can only be reset on boot
+    NotResettable,
+    /// The register selection is empty, unsorted or contains duplicates
+    InvalidSelection,
+}
+
+impl<E: digest::Error> Error<E> {
+    /// Convert error to a generic error kind.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Digest(e) => ErrorKind::Digest(e.kind()),
+            Error::InvalidIndex => ErrorKind::InvalidIndex,
+            Error::Locked => ErrorKind::Locked,
+            Error::NotResettable => ErrorKind::NotResettable,
+            Error::InvalidSelection => ErrorKind::InvalidSelection,
+        }
+    }
+}
+
+/// Error returned by [`MeasurementBank::quote`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuoteError<DigestError, SignError> {
+    /// Reading or hashing the selected registers failed
+    Measurement(Error<DigestError>),
+    /// Signing the quote failed
+    Sign(SignError),
+}
+
+impl<DE: digest::Error, SE: ecdsa::Error> QuoteError<DE, SE> {
+    /// Convert error to a generic error kind.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            QuoteError::Measurement(e) => e.kind(),
+            QuoteError::Sign(e) => ErrorKind::Sign(e.kind()),
+        }
+    }
+}
+
+/// When a register returns to its initial value.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ResetPolicy {
+    /// The register is only reset on boot.
+    OnBoot,
+    /// The register is reset on boot and can also be reset at runtime.
+    Resettable,
+}
+
+/// Value of a register in a bank computed with `D`.
+pub type RegisterValue<D> = <<D as Digest>::Algorithm as DigestAlgorithm>::DigestOutput;
+
+#[derive(Debug, Copy, Clone)]
+struct Register<V> {
+    value: V,
+    locked: bool,
+    policy: ResetPolicy,
+}
+
+/// A signed attestation of register values.
+#[derive(Debug, Clone)]
+pub struct Quote<V, S> {
+    /// Digest over the nonce and the selected registers.
+    pub digest: V,
+    /// Signature over `digest`.
+    pub signature: S,
+}
+
+/// A quote over the registers of a bank computed with `D`, signed with `S`.
+pub type SignedQuote<D, S> = Quote<RegisterValue<D>, <S as EcdsaSign>::Signature>;
+
+/// A bank of `N` measurement registers extended with one hash algorithm.
+///
+/// All registers start out as zero and unlocked. Creating the bank is the boot
+/// reset: create it once per boot, before the first measurement. There is no
+/// way to clear a lock bit afterwards, so a locked register keeps its value
+/// until the bank is dropped.
+pub struct MeasurementBank<D: Digest, const N: usize> {
+    digest: D,
+    registers: [Register<RegisterValue<D>>; N],
+}
+
+impl<D: Digest, const N: usize> MeasurementBank<D, N> {
+    /// Create a bank extended through `digest`, with one reset policy per register.
+    pub fn new(digest: D, policies: [ResetPolicy; N]) -> Self {
+        Self {
+            digest,
+            registers: policies.map(|policy| Register {
+                value: RegisterValue::<D>::default(),
+                locked: false,
+                policy,
+            }),
+        }
+    }
+
+    /// Extend a register with a measurement.
+    ///
+    /// # Parameters
+    ///
+    /// - `index`: The register to extend.
+    /// - `measurement`: The measurement, typically the digest of the measured object.
+    pub fn extend(&mut self, index: usize, measurement: &[u8]) -> Result<(), Error<D::Error>> {
+        let register = self.registers.get(index).ok_or(Error::InvalidIndex)?;
+        if register.locked {
+            return Err(Error::Locked);
+        }
+
+        self.digest.reset().map_err(Error::Digest)?;
+        self.digest
+            .update_vectored(&[register.value.as_ref(), measurement])
+            .map_err(Error::Digest)?;
+        self.registers[index].value = self.digest.finalize().map_err(Error::Digest)?;
+        Ok(())
+    }
+
+    /// Read the current value of a register.
+    pub fn read(&self, index: usize) -> Result<&RegisterValue<D>, Error<D::Error>> {
+        self.registers
+            .get(index)
+            .map(|register| &register.value)
+            .ok_or(Error::InvalidIndex)
+    }
+
+    /// Lock a register against further extension until the next boot.
+    pub fn lock(&mut self, index: usize) -> Result<(), Error<D::Error>> {
+        let register = self.registers.get_mut(index).ok_or(Error::InvalidIndex)?;
+        register.locked = true;
+        Ok(())
+    }
+
+    /// Returns whether a register is locked.
+    pub fn is_locked(&self, index: usize) -> Result<bool, Error<D::Error>> {
+        self.registers
+            .get(index)
+            .map(|register| register.locked)
+            .ok_or(Error::InvalidIndex)
+    }
+
+    /// Reset a register to zero at runtime.
+    ///
+    /// Only registers with [`ResetPolicy::Resettable`] that are not locked can
+    /// be reset.
+    pub fn reset(&mut self, index: usize) -> Result<(), Error<D::Error>> {
+        let register = self.registers.get_mut(index).ok_or(Error::InvalidIndex)?;
+        if register.policy != ResetPolicy::Resettable {
+            return Err(Error::NotResettable);
+        }
+        if register.locked {
+            return Err(Error::Locked);
+        }
+        register.value = RegisterValue::<D>::default();
+        Ok(())
+    }
+
+    /// Produce a signed quote over a selection of registers.
+    ///
+    /// The quote digest is computed with the bank's algorithm over the nonce
+    /// followed by, for each selected register, its index as a big-endian
+    /// `u32` and its value. The digest is then signed with `S`, with the bank's
+    /// algorithm as the hash marker. Fails to compile if the bank's hash is
+    /// weaker than the curve.
+    ///
+    /// # Parameters
+    ///
+    /// - `signer`: The signing implementation.
+    /// - `selection`: Indices of the registers to quote, in strictly ascending order.
+    /// - `nonce`: Caller-provided freshness value.
+    /// - `curve`: The elliptic curve of the signing key.
+    /// - `private_key`: The key to sign the quote with.
+    pub fn quote<S: EcdsaSign>(
+        &mut self,
+        signer: &mut S,
+        selection: &[usize],
+        nonce: &[u8],
+        curve: &S::Curve,
+        private_key: &S::PrivateKey,
+    ) -> Result<SignedQuote<D, S>, QuoteError<D::Error, S::Error>> {
+        const {
+            assert!(
+                hash_matches_curve::<D::Algorithm, S::Curve>(),
+                "the hash is weaker than the curve"
+            )
+        };
+        let valid = !selection.is_empty()
+            && selection.windows(2).all(|pair| pair[0] < pair[1])
+            && selection.iter().all(|index| *index <= u32::MAX as usize);
+        if !valid {
+            return Err(QuoteError::Measurement(Error::InvalidSelection));
+        }
+
+        let digest = self
+            .quote_digest(selection, nonce)
+            .map_err(QuoteError::Measurement)?;
+        let signature = signer
+            .sign::<D::Algorithm>(curve, private_key, digest.as_ref())
+            .map_err(QuoteError::Sign)?;
+        Ok(Quote { digest, signature })
+    }
+
+    fn quote_digest(
+        &mut self,
+        selection: &[usize],
+        nonce: &[u8],
+    ) -> Result<RegisterValue<D>, Error<D::Error>> {
+        self.digest.reset().map_err(Error::Digest)?;
+        self.digest.update(nonce).map_err(Error::Digest)?;
+        for index in selection {
+            let register = self.registers.get(*index).ok_or(Error::InvalidIndex)?;
+            let encoded_index = (*index as u32).to_be_bytes();
+            self.digest
+                .update_vectored(&[&encoded_index, register.value.as_ref()])
+                .map_err(Error::Digest)?;
+        }
+        self.digest.finalize().map_err(Error::Digest)
+    }
+}
+
+/// Algorithm-independent access to a measurement bank.
+///
+/// Allows banks of different hash algorithms to be handled together, for
+/// example to record each measurement in every bank with [`extend_all`].
+pub trait Bank {
+    /// Size in bytes of a register value.
+    fn value_size(&self) -> usize;
+
+    /// See [`MeasurementBank::extend`].
+    fn extend(&mut self, index: usize, measurement: &[u8]) -> Result<(), ErrorKind>;
+
+    /// See [`MeasurementBank::read`].
+    fn read(&self, index: usize) -> Result<&[u8], ErrorKind>;
+
+    /// See [`MeasurementBank::lock`].
+    fn lock(&mut self, index: usize) -> Result<(), ErrorKind>;
+
+    /// See [`MeasurementBank::is_locked`].
+    fn is_locked(&self, index: usize) -> Result<bool, ErrorKind>;
+
+    /// See [`MeasurementBank::reset`].
+    fn reset(&mut self, index: usize) -> Result<(), ErrorKind>;
+}
+
+impl<D: Digest, const N: usize> Bank for MeasurementBank<D, N> {
+    fn value_size(&self) -> usize {
+        D::Algorithm::OUTPUT_SIZE
+    }
+
+    fn extend(&mut self, index: usize, measurement: &[u8]) -> Result<(), ErrorKind> {
+        MeasurementBank::extend(self, index, measurement).map_err(|e| e.kind())
+    }
+
+    fn read(&self, index: usize) -> Result<&[u8], ErrorKind> {
+        MeasurementBank::read(self, index)
+            .map(|value| value.as_ref())
+            .map_err(|e| e.kind())
+    }
+
+    fn lock(&mut self, index: usize) -> Result<(), ErrorKind> {
+        MeasurementBank::lock(self, index).map_err(|e| e.kind())
+    }
+
+    fn is_locked(&self, index: usize) -> Result<bool, ErrorKind> {
+        MeasurementBank::is_locked(self, index).map_err(|e| e.kind())
+    }
+
+    fn reset(&mut self, index: usize) -> Result<(), ErrorKind> {
+        MeasurementBank::reset(self, index).map_err(|e| e.kind())
+    }
+}
+
+/// Extend the same register in every bank with a measurement.
+///
+/// The register is checked to exist and be unlocked in every bank before any
+/// bank is extended. A digest failure part way through leaves the banks before
+/// the failing one extended.
+pub fn extend_all(
+    banks: &mut [&mut dyn Bank],
+    index: usize,
+    measurement: &[u8],
+) -> Result<(), ErrorKind> {
+    for bank in banks.iter() {
+        if bank.is_locked(index)? {
+            return Err(ErrorKind::Locked);
+        }
+    }
+    for bank in banks {
+        bank.extend(index, measurement)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,249 @@
+//! Deterministic ECDSA nonce generation (RFC 6979) over any [`Mac`] implementation.
+//!
+//! The MAC must be HMAC with the hash function that produced the message hash.
+//! Passing additional input gives the hedged variant of section 3.6, which
+//! mixes fresh entropy into the derivation without depending on its quality.
+
+use core::marker::PhantomData;
+
+use crate::ecdsa::encoding::MAX_SCALAR_SIZE;
+use crate::ecdsa::{self, EcdsaCurve, ErrorKind};
+use crate::key::KeyHandle;
+use crate::mac::{self, Mac, MacAlgorithm};
+
+/// Largest HMAC output size supported, that of HMAC-SHA-512.
+pub const MAX_HASH_SIZE: usize = 64;
+
+/// Error returned by [`NonceGenerator`] and [`generate_k`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error<MacError> {
+    /// Underlying MAC error
+    Mac(MacError),
+
+    /// The private key is not [`EcdsaCurve::SCALAR_SIZE`] bytes or not in `[1, n - 1]`
+    InvalidPrivateKey,
+    /// The output buffer is smaller than [`EcdsaCurve::SCALAR_SIZE`]
+    BufferTooSmall,
+}
+
+impl<E: mac::Error> ecdsa::Error for Error<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Mac(e) if e.kind() == mac::ErrorKind::HardwareAcceleratorBusy => ErrorKind::Busy,
+            Error::Mac(_) | Error::InvalidPrivateKey | Error::BufferTooSmall => {
+                ErrorKind::SigningError
+            }
+        }
+    }
+}
+
+/// Computes `a - b` into `out` and returns the borrow, 1 if `a < b`.
+///
+/// Runs in time independent of the values.
+fn sub(a: &[u8], b: &[u8], out: &mut [u8]) -> u8 {
+    let mut borrow = 0u16;
+    for ((a, b), out) in a.iter().zip(b).zip(out).rev() {
+        let d = u16::from(*a)
+            .wrapping_sub(u16::from(*b))
+            .wrapping_sub(borrow);
+        *out = d as u8;
+        borrow = d >> 15;
+    }
+    borrow as u8
+}
+
+/// Whether the scalar is in `[1, n - 1]`.
+fn is_in_range<C: EcdsaCurve>(scalar: &[u8]) -> bool {
+    let mut difference = [0u8; MAX_SCALAR_SIZE];
+    let below_order = sub(scalar, C::ORDER, &mut difference[..C::SCALAR_SIZE]);
+    let nonzero = scalar.iter().fold(0, |acc, b| acc | b);
+    (below_order == 1) & (nonzero != 0)
+}
+
+/// `bits2int` of RFC 6979: the leftmost `qlen` bits of `bits`, as a
+/// big-endian integer of [`EcdsaCurve::SCALAR_SIZE`] bytes.
+fn bits2int<C: EcdsaCurve>(bits: &[u8], out: &mut [u8]) {
+    let rlen = C::SCALAR_SIZE;
+    let out = &mut out[..rlen];
+    if bits.len() < rlen {
+        let (zeros, value) = out.split_at_mut(rlen - bits.len());
+        zeros.fill(0);
+        value.copy_from_slice(bits);
+        return;
+    }
+
+    // qlen is 8 * rlen minus the leading zero bits of the order.
+    let shift = C::ORDER[0].leading_zeros();
+    out.copy_from_slice(&bits[..rlen]);
+    if shift > 0 {
+        for i in (0..rlen).rev() {
+            let carry = if i > 0 { out[i - 1] << (8 - shift) } else { 0 };
+            out[i] = (out[i] >> shift) | carry;
+        }
+    }
+}
+
+/// HMAC_DRBG state of RFC 6979 section 3.2, producing nonce candidates for one signature.
+///
+/// The first candidate is the RFC 6979 nonce. Further candidates are only
+/// needed if the signature computed with the previous one has `r = 0` or `s = 0`.
+pub struct NonceGenerator<'m, M, C> {
+    mac: &'m mut M,
+    key: [u8; MAX_HASH_SIZE],
+    value: [u8; MAX_HASH_SIZE],
+    started: bool,
+    _curve: PhantomData<C>,
+}
+
+impl<'m, M: Mac, C: EcdsaCurve> NonceGenerator<'m, M, C> {
+    const HASH_SIZE: usize = const {
+        assert!(<M::Algorithm as MacAlgorithm>::TAG_SIZE <= MAX_HASH_SIZE);
+        assert!(C::SCALAR_SIZE <= MAX_SCALAR_SIZE);
+        <M::Algorithm as MacAlgorithm>::TAG_SIZE
+    };
+
+    /// Seeds the generator, steps a. to g. of RFC 6979 section 3.2.
+    ///
+    /// # Parameters
+    ///
+    /// - `mac`: The HMAC computation to use. Its key is replaced.
+    /// - `private_key`: The private key `x`, big-endian, [`EcdsaCurve::SCALAR_SIZE`] bytes.
+    /// - `message_hash`: The hash `H(m)` of the message to sign.
+    /// - `extra_entropy`: The additional input `k'` of section 3.6, empty for
+    ///   the deterministic variant.
+    ///
+    /// # Returns
+    ///
+    /// The seeded generator, or an error.
+    pub fn new(
+        mac: &'m mut M,
+        private_key: &[u8],
+        message_hash: &[u8],
+        extra_entropy: &[u8],
+    ) -> Result<Self, Error<M::Error>> {
+        let h = Self::HASH_SIZE;
+        if private_key.len() != C::SCALAR_SIZE || !is_in_range::<C>(private_key) {
+            return Err(Error::InvalidPrivateKey);
+        }
+
+        // bits2octets(H(m)): bits2int reduced modulo n, which needs at most
+        // one subtraction.
+        let mut hash = [0u8; MAX_SCALAR_SIZE];
+        let mut reduced = [0u8; MAX_SCALAR_SIZE];
+        let hash = &mut hash[..C::SCALAR_SIZE];
+        bits2int::<C>(message_hash, hash);
+        let borrow = sub(hash, C::ORDER, &mut reduced[..C::SCALAR_SIZE]);
+        let keep = borrow.wrapping_neg();
+        for (hash, reduced) in hash.iter_mut().zip(reduced) {
+            *hash = (*hash & keep) | (reduced & !keep);
+        }
+
+        let mut generator = Self {
+            mac,
+            key: [0; MAX_HASH_SIZE],
+            value: [1; MAX_HASH_SIZE],
+            started: false,
+            _curve: PhantomData,
+        };
+        for separator in [[0x00], [0x01]] {
+            let value = generator.value;
+            generator.update_key(&[&value[..h], &separator, private_key, hash, extra_entropy])?;
+            generator.update_value()?;
+        }
+        Ok(generator)
+    }
+
+    /// `K = HMAC_K(data)`.
+    fn update_key(&mut self, data: &[&[u8]]) -> Result<(), Error<M::Error>> {
+        let h = Self::HASH_SIZE;
+        let mut key = [0u8; MAX_HASH_SIZE];
+        self.mac
+            .set_key(KeyHandle::Raw(&self.key[..h]))
+            .map_err(Error::Mac)?;
+        self.mac.update_vectored(data).map_err(Error::Mac)?;
+        self.mac.finalize(&mut key[..h]).map_err(Error::Mac)?;
+        self.key = key;
+        key.fill(0);
+        Ok(())
+    }
+
+    /// `V = HMAC_K(V)`.
+    fn update_value(&mut self) -> Result<(), Error<M::Error>> {
+        let h = Self::HASH_SIZE;
+        let mut value = [0u8; MAX_HASH_SIZE];
+        self.mac
+            .set_key(KeyHandle::Raw(&self.key[..h]))
+            .map_err(Error::Mac)?;
+        self.mac.update(&self.value[..h]).map_err(Error::Mac)?;
+        self.mac.finalize(&mut value[..h]).map_err(Error::Mac)?;
+        self.value = value;
+        Ok(())
+    }
+
+    /// Produces the next nonce candidate, step h. of RFC 6979 section 3.2.
+    ///
+    /// # Parameters
+    ///
+    /// - `k`: Buffer receiving the nonce, big-endian, in its first
+    ///   [`EcdsaCurve::SCALAR_SIZE`] bytes.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure. On success, returns `Ok(())`.
+    pub fn next_k(&mut self, k: &mut [u8]) -> Result<(), Error<M::Error>> {
+        let h = Self::HASH_SIZE;
+        let k = k.get_mut(..C::SCALAR_SIZE).ok_or(Error::BufferTooSmall)?;
+
+        let mut t = [0u8; MAX_SCALAR_SIZE + MAX_HASH_SIZE];
+        loop {
+            if self.started {
+                let value = self.value;
+                self.update_key(&[&value[..h], &[0x00]])?;
+                self.update_value()?;
+            }
+            self.started = true;
+
+            let mut len = 0;
+            while len < C::SCALAR_SIZE {
+                self.update_value()?;
+                t[len..len + h].copy_from_slice(&self.value[..h]);
+                len += h;
+            }
+            bits2int::<C>(&t[..len], k);
+            if is_in_range::<C>(k) {
+                t.fill(0);
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<M, C> Drop for NonceGenerator<'_, M, C> {
+    fn drop(&mut self) {
+        self.key.fill(0);
+        self.value.fill(0);
+    }
+}
+
+/// Generates the RFC 6979 nonce for one signature.
+///
+/// # Parameters
+///
+/// - `mac`: The HMAC computation to use. Its key is replaced.
+/// - `private_key`: The private key `x`, big-endian, [`EcdsaCurve::SCALAR_SIZE`] bytes.
+/// - `message_hash`: The hash `H(m)` of the message to sign.
+/// - `extra_entropy`: The additional input `k'`, empty for the deterministic variant.
+/// - `k`: Buffer receiving the nonce in its first [`EcdsaCurve::SCALAR_SIZE`] bytes.
+///
+/// # Returns
+///
+/// A `Result` indicating success or failure. On success, returns `Ok(())`.
+pub fn generate_k<C: EcdsaCurve, M: Mac>(
+    mac: &mut M,
+    private_key: &[u8],
+    message_hash: &[u8],
+    extra_entropy: &[u8],
+    k: &mut [u8],
+) -> Result<(), Error<M::Error>> {
+    NonceGenerator::<M, C>::new(mac, private_key, message_hash, extra_entropy)?.next_k(k)
+}
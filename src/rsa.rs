@@ -36,6 +36,8 @@ pub trait ErrorType {
 }
 
 pub trait RsaKeys {
+    /// The private key, or a reference to it such as a
+    /// [`KeyHandle`](crate::key::KeyHandle) resolved by the backend.
     type PrivateKey;
     type PublicKey;
 }
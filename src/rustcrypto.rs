@@ -0,0 +1,550 @@
+//! Adapters between the traits of this crate and the RustCrypto
+//! [`digest`](::digest) and [`signature`](::signature) traits.
+//!
+//! The adapters work in both directions:
+//!
+//! - [`Hasher`], [`EcdsaSigner`] and [`EcdsaVerifier`] make implementations of
+//!   this crate's traits usable where RustCrypto traits are expected.
+//! - [`RustCryptoDigest`], [`RustCryptoMac`] and [`RustCryptoEcdsa`] wrap
+//!   RustCrypto implementations so they can act as backends of this crate's
+//!   traits.
+//!
+//! The RustCrypto hashing traits are infallible. [`Hasher`] therefore panics if
+//! the wrapped digest reports an error.
+//!
+//! The RustCrypto signature traits take the signer by shared reference, while
+//! the ECDSA traits of this crate need exclusive access to the backend.
+//! [`EcdsaSigner`] and [`EcdsaVerifier`] keep the backend in a [`RefCell`].
+
+use core::cell::RefCell;
+use core::marker::PhantomData;
+
+use ::digest::consts::{U28, U32, U48, U64};
+use ::digest::generic_array::ArrayLength;
+use ::digest::typenum::Unsigned;
+use ::digest::{FixedOutput, FixedOutputReset, KeyInit, MacMarker, OutputSizeUser, Reset, Update};
+use ::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use ::signature::{Signer, Verifier};
+use ecdsa_core::elliptic_curve::sec1::{FromEncodedPoint, ModulusSize, ToEncodedPoint};
+use ecdsa_core::elliptic_curve::{AffinePoint, CurveArithmetic, FieldBytesSize, PrimeCurve};
+use ecdsa_core::VerifyingKey;
+
+use crate::digest::{
+    Digest, DigestAlgorithm, DigestInit, ErrorType, Sha2_224, Sha2_256, Sha2_384, Sha2_512,
+    Sha3_224, Sha3_256, Sha3_384, Sha3_512,
+};
+use crate::ecdsa::encoding::Sec1Point;
+use crate::ecdsa::{
+    self, message, EcdsaCurve, EcdsaSign, EcdsaValidate, EcdsaVerify, ErrorKind, HashMarker,
+};
+use crate::key::{KeyHandle, Validated};
+use crate::mac::{self, Mac, MacAlgorithm};
+
+/// A digest algorithm whose output size is known to RustCrypto.
+pub trait RustCryptoAlgorithm: DigestAlgorithm {
+    /// The output size as a type-level integer.
+    type OutputSize: ArrayLength<u8> + 'static;
+}
+
+impl RustCryptoAlgorithm for Sha2_224 {
+    type OutputSize = U28;
+}
+
+impl RustCryptoAlgorithm for Sha2_256 {
+    type OutputSize = U32;
+}
+
+impl RustCryptoAlgorithm for Sha2_384 {
+    type OutputSize = U48;
+}
+
+impl RustCryptoAlgorithm for Sha2_512 {
+    type OutputSize = U64;
+}
+
+impl RustCryptoAlgorithm for Sha3_224 {
+    type OutputSize = U28;
+}
+
+impl RustCryptoAlgorithm for Sha3_256 {
+    type OutputSize = U32;
+}
+
+impl RustCryptoAlgorithm for Sha3_384 {
+    type OutputSize = U48;
+}
+
+impl RustCryptoAlgorithm for Sha3_512 {
+    type OutputSize = U64;
+}
+
+/// Exposes a [`Digest`] implementation as a RustCrypto hasher.
+///
+/// # Panics
+///
+/// The RustCrypto traits cannot report errors, so every method panics if the
+/// wrapped digest fails.
+#[derive(Debug, Default, Clone)]
+pub struct Hasher<D> {
+    digest: D,
+}
+
+impl<D> Hasher<D> {
+    pub fn new(digest: D) -> Self {
+        Self { digest }
+    }
+
+    /// Returns the wrapped digest.
+    pub fn into_inner(self) -> D {
+        self.digest
+    }
+}
+
+impl<D: Digest> Update for Hasher<D> {
+    fn update(&mut self, data: &[u8]) {
+        self.digest.update(data).expect("digest update failed");
+    }
+}
+
+impl<D> OutputSizeUser for Hasher<D>
+where
+    D: Digest,
+    D::Algorithm: RustCryptoAlgorithm,
+{
+    type OutputSize = <D::Algorithm as RustCryptoAlgorithm>::OutputSize;
+}
+
+impl<D> FixedOutput for Hasher<D>
+where
+    D: Digest,
+    D::Algorithm: RustCryptoAlgorithm,
+{
+    fn finalize_into(mut self, out: &mut ::digest::Output<Self>) {
+        let digest = self.digest.finalize().expect("digest finalization failed");
+        out.copy_from_slice(digest.as_ref());
+    }
+}
+
+impl<D: Digest> Reset for Hasher<D> {
+    fn reset(&mut self) {
+        self.digest.reset().expect("digest reset failed");
+    }
+}
+
+impl<D> FixedOutputReset for Hasher<D>
+where
+    D: Digest,
+    D::Algorithm: RustCryptoAlgorithm,
+{
+    fn finalize_into_reset(&mut self, out: &mut ::digest::Output<Self>) {
+        let digest = self.digest.finalize().expect("digest finalization failed");
+        out.copy_from_slice(digest.as_ref());
+        self.digest.reset().expect("digest reset failed");
+    }
+}
+
+impl<D: Digest> ::digest::HashMarker for Hasher<D> {}
+
+/// Exposes a RustCrypto hasher as a [`Digest`] implementation.
+///
+/// `A` names the algorithm computed by the hasher. Its output size must match
+/// the hasher's, which is checked at compile time.
+#[derive(Debug, Default, Clone)]
+pub struct RustCryptoDigest<D, A> {
+    hasher: D,
+    _algorithm: PhantomData<A>,
+}
+
+impl<D, A> RustCryptoDigest<D, A> {
+    pub fn new(hasher: D) -> Self {
+        Self {
+            hasher,
+            _algorithm: PhantomData,
+        }
+    }
+}
+
+impl<D, A> ErrorType for RustCryptoDigest<D, A> {
+    type Error = core::convert::Infallible;
+}
+
+impl<D, A> Digest for RustCryptoDigest<D, A>
+where
+    D: Update + FixedOutputReset + OutputSizeUser<OutputSize = A::OutputSize>,
+    A: RustCryptoAlgorithm,
+{
+    type Algorithm = A;
+
+    fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+        Update::update(&mut self.hasher, input);
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), Self::Error> {
+        Reset::reset(&mut self.hasher);
+        Ok(())
+    }
+
+    fn finalize(&mut self) -> Result<A::DigestOutput, Self::Error> {
+        let mut out = A::DigestOutput::default();
+        out.as_mut()
+            .copy_from_slice(&self.hasher.finalize_fixed_reset());
+        Ok(out)
+    }
+}
+
+/// A hash engine creating [`RustCryptoDigest`] contexts.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RustCryptoEngine<D> {
+    _hasher: PhantomData<D>,
+}
+
+impl<D> ErrorType for RustCryptoEngine<D> {
+    type Error = core::convert::Infallible;
+}
+
+impl<D, A> DigestInit<A> for RustCryptoEngine<D>
+where
+    D: Default + Update + FixedOutputReset + OutputSizeUser<OutputSize = A::OutputSize>,
+    A: RustCryptoAlgorithm,
+{
+    type OpContext<'a>
+        = RustCryptoDigest<D, A>
+    where
+        Self: 'a;
+
+    fn init(&mut self, _init_params: A) -> Result<Self::OpContext<'_>, Self::Error> {
+        Ok(RustCryptoDigest::new(D::default()))
+    }
+}
+
+/// Exposes a RustCrypto MAC, such as `hmac::Hmac`, as a [`Mac`] implementation.
+///
+/// `A` names the algorithm computed by the MAC. Its tag size must match the
+/// MAC's output size, which is checked at compile time. Only
+/// [`KeyHandle::Raw`] keys are accepted.
+#[derive(Debug, Clone)]
+pub struct RustCryptoMac<M, A> {
+    /// The MAC right after keying, restored on reset and after each tag.
+    keyed: Option<M>,
+    mac: Option<M>,
+    _algorithm: PhantomData<A>,
+}
+
+impl<M, A> RustCryptoMac<M, A>
+where
+    M: OutputSizeUser,
+    A: MacAlgorithm,
+{
+    pub fn new() -> Self {
+        const {
+            assert!(
+                M::OutputSize::USIZE == A::TAG_SIZE,
+                "the MAC output size does not match the algorithm"
+            )
+        };
+        Self {
+            keyed: None,
+            mac: None,
+            _algorithm: PhantomData,
+        }
+    }
+}
+
+impl<M, A> Default for RustCryptoMac<M, A>
+where
+    M: OutputSizeUser,
+    A: MacAlgorithm,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M, A> RustCryptoMac<M, A>
+where
+    M: Update + FixedOutput + MacMarker + Clone,
+{
+    fn compute(&mut self) -> Result<::digest::Output<M>, mac::ErrorKind> {
+        let keyed = self.keyed.clone().ok_or(mac::ErrorKind::NotInitialized)?;
+        let mac = self
+            .mac
+            .replace(keyed)
+            .ok_or(mac::ErrorKind::NotInitialized)?;
+        Ok(mac.finalize_fixed())
+    }
+}
+
+impl<M, A> mac::ErrorType for RustCryptoMac<M, A> {
+    type Error = mac::ErrorKind;
+}
+
+impl<M, A> Mac for RustCryptoMac<M, A>
+where
+    M: Update + FixedOutput + MacMarker + KeyInit + Clone,
+    A: MacAlgorithm,
+{
+    type Algorithm = A;
+
+    fn set_key(&mut self, key: KeyHandle<'_>) -> Result<(), Self::Error> {
+        let key = key.as_raw().ok_or(mac::ErrorKind::UnsupportedKeyHandle)?;
+        self.keyed = None;
+        self.mac = None;
+        if !A::KEY_SIZE.contains(key.len()) {
+            return Err(mac::ErrorKind::InvalidKeySize);
+        }
+        let mac = M::new_from_slice(key).map_err(|_| mac::ErrorKind::InvalidKeySize)?;
+        self.mac = Some(mac.clone());
+        self.keyed = Some(mac);
+        Ok(())
+    }
+
+    fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+        let mac = self.mac.as_mut().ok_or(mac::ErrorKind::NotInitialized)?;
+        Update::update(mac, input);
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), Self::Error> {
+        self.mac = Some(self.keyed.clone().ok_or(mac::ErrorKind::NotInitialized)?);
+        Ok(())
+    }
+
+    fn finalize(&mut self, out: &mut [u8]) -> Result<(), Self::Error> {
+        if out.len() < A::TAG_SIZE {
+            return Err(mac::ErrorKind::InvalidOutputSize);
+        }
+        let tag = self.compute()?;
+        out[..A::TAG_SIZE].copy_from_slice(&tag);
+        Ok(())
+    }
+
+    fn verify(&mut self, tag: &[u8]) -> Result<(), Self::Error> {
+        let expected = self.compute()?;
+        mac::verify_tag(&expected, tag, A::MIN_TAG_SIZE)
+    }
+}
+
+/// Exposes an [`EcdsaSign`] implementation as a RustCrypto signer.
+///
+/// `H` is the hash marker passed to [`EcdsaSign::sign`]. When signing complete
+/// messages through [`Signer`], the message is hashed with `D`, which must
+/// compute `H` and be at least as strong as the curve.
+pub struct EcdsaSigner<S: EcdsaSign, H, D = ()> {
+    signer: RefCell<S>,
+    curve: S::Curve,
+    private_key: S::PrivateKey,
+    _hash: PhantomData<(H, D)>,
+}
+
+impl<S: EcdsaSign, H, D> EcdsaSigner<S, H, D> {
+    pub fn new(signer: S, curve: S::Curve, private_key: S::PrivateKey) -> Self {
+        Self {
+            signer: RefCell::new(signer),
+            curve,
+            private_key,
+            _hash: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped signing implementation.
+    pub fn into_inner(self) -> S {
+        self.signer.into_inner()
+    }
+}
+
+impl<S: EcdsaSign, H: HashMarker, D> PrehashSigner<S::Signature> for EcdsaSigner<S, H, D> {
+    fn sign_prehash(&self, prehash: &[u8]) -> Result<S::Signature, ::signature::Error> {
+        let mut signer = self
+            .signer
+            .try_borrow_mut()
+            .map_err(|_| ::signature::Error::new())?;
+        signer
+            .sign::<H>(&self.curve, &self.private_key, prehash)
+            .map_err(|_| ::signature::Error::new())
+    }
+}
+
+impl<S, H, D> Signer<S::Signature> for EcdsaSigner<S, H, D>
+where
+    S: EcdsaSign,
+    H: HashMarker,
+    D: Digest<Algorithm = H> + Default,
+{
+    fn try_sign(&self, msg: &[u8]) -> Result<S::Signature, ::signature::Error> {
+        let mut signer = self
+            .signer
+            .try_borrow_mut()
+            .map_err(|_| ::signature::Error::new())?;
+        message::sign_message(
+            &mut *signer,
+            &self.curve,
+            &self.private_key,
+            &mut D::default(),
+            msg,
+        )
+        .map_err(|_| ::signature::Error::new())
+    }
+}
+
+/// Exposes an [`EcdsaVerify`] implementation as a RustCrypto verifier.
+///
+/// `H` is the hash marker passed to [`EcdsaVerify::verify`]. When verifying
+/// complete messages through [`Verifier`], the message is hashed with `D`,
+/// which must compute `H` and be at least as strong as the curve.
+pub struct EcdsaVerifier<V: EcdsaVerify, H, D = ()> {
+    verifier: RefCell<V>,
+    curve: V::Curve,
+    public_key: Validated<V::PublicKey>,
+    _hash: PhantomData<(H, D)>,
+}
+
+impl<V: EcdsaVerify, H, D> EcdsaVerifier<V, H, D> {
+    pub fn new(verifier: V, curve: V::Curve, public_key: Validated<V::PublicKey>) -> Self {
+        Self {
+            verifier: RefCell::new(verifier),
+            curve,
+            public_key,
+            _hash: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped verification implementation.
+    pub fn into_inner(self) -> V {
+        self.verifier.into_inner()
+    }
+}
+
+impl<V: EcdsaVerify, H: HashMarker, D> PrehashVerifier<V::Signature> for EcdsaVerifier<V, H, D> {
+    fn verify_prehash(
+        &self,
+        prehash: &[u8],
+        signature: &V::Signature,
+    ) -> Result<(), ::signature::Error> {
+        let mut verifier = self
+            .verifier
+            .try_borrow_mut()
+            .map_err(|_| ::signature::Error::new())?;
+        verifier
+            .verify::<H>(&self.curve, &self.public_key, prehash, signature)
+            .map_err(|_| ::signature::Error::new())
+    }
+}
+
+impl<V, H, D> Verifier<V::Signature> for EcdsaVerifier<V, H, D>
+where
+    V: EcdsaVerify,
+    H: HashMarker,
+    D: Digest<Algorithm = H> + Default,
+{
+    fn verify(&self, msg: &[u8], signature: &V::Signature) -> Result<(), ::signature::Error> {
+        let mut verifier = self
+            .verifier
+            .try_borrow_mut()
+            .map_err(|_| ::signature::Error::new())?;
+        message::verify_message(
+            &mut *verifier,
+            &self.curve,
+            &self.public_key,
+            &mut D::default(),
+            msg,
+            signature,
+        )
+        .map_err(|_| ::signature::Error::new())
+    }
+}
+
+/// Exposes RustCrypto ECDSA keys as an [`EcdsaSign`] and [`EcdsaVerify`] backend.
+///
+/// `SK` is the signing key type, `VK` the verifying key type, `S` the signature
+/// type and `C` the curve the keys belong to. Public keys can only be
+/// validated, and signatures verified, when `VK` is an `ecdsa::VerifyingKey`.
+pub struct RustCryptoEcdsa<SK, VK, S, C> {
+    _types: PhantomData<(SK, VK, S, C)>,
+}
+
+impl<SK, VK, S, C> RustCryptoEcdsa<SK, VK, S, C> {
+    pub const fn new() -> Self {
+        Self {
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<SK, VK, S, C> Default for RustCryptoEcdsa<SK, VK, S, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<SK, VK, S, C> ecdsa::ErrorType for RustCryptoEcdsa<SK, VK, S, C> {
+    type Error = ErrorKind;
+}
+
+impl<SK, VK, S, C> EcdsaSign for RustCryptoEcdsa<SK, VK, S, C>
+where
+    SK: PrehashSigner<S>,
+    C: EcdsaCurve,
+{
+    type PrivateKey = SK;
+    type Curve = C;
+    type Signature = S;
+
+    fn sign<H: HashMarker>(
+        &mut self,
+        _curve: &Self::Curve,
+        private_key: &Self::PrivateKey,
+        message_hash: impl AsRef<[u8]>,
+    ) -> Result<Self::Signature, Self::Error> {
+        private_key
+            .sign_prehash(message_hash.as_ref())
+            .map_err(|_| ErrorKind::SigningError)
+    }
+}
+
+/// `ecdsa::VerifyingKey` only holds points on its curve other than the
+/// identity, which it checks when decoded. The point is still encoded and
+/// parsed again for `C`, which rejects a key of another curve than `C`.
+impl<SK, K, S, C> EcdsaValidate for RustCryptoEcdsa<SK, VerifyingKey<K>, S, C>
+where
+    K: PrimeCurve + CurveArithmetic,
+    AffinePoint<K>: FromEncodedPoint<K> + ToEncodedPoint<K>,
+    FieldBytesSize<K>: ModulusSize,
+    C: EcdsaCurve,
+{
+    type PublicKey = VerifyingKey<K>;
+    type Curve = C;
+
+    fn check_public_key(
+        &mut self,
+        _curve: &Self::Curve,
+        public_key: &Self::PublicKey,
+    ) -> Result<(), Self::Error> {
+        let point = public_key.to_encoded_point(false);
+        Sec1Point::parse::<C>(point.as_bytes()).map_err(|_| ErrorKind::InvalidPublicKey)?;
+        Ok(())
+    }
+}
+
+impl<SK, K, S, C> EcdsaVerify for RustCryptoEcdsa<SK, VerifyingKey<K>, S, C>
+where
+    K: PrimeCurve + CurveArithmetic,
+    AffinePoint<K>: FromEncodedPoint<K> + ToEncodedPoint<K>,
+    FieldBytesSize<K>: ModulusSize,
+    VerifyingKey<K>: PrehashVerifier<S>,
+    C: EcdsaCurve,
+{
+    type Signature = S;
+
+    fn verify<H: HashMarker>(
+        &mut self,
+        _curve: &Self::Curve,
+        public_key: &Validated<Self::PublicKey>,
+        message_hash: impl AsRef<[u8]>,
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error> {
+        public_key
+            .verify_prehash(message_hash.as_ref(), signature)
+            .map_err(|_| ErrorKind::InvalidSignature)
+    }
+}
@@ -0,0 +1,144 @@
+//! AES block cipher (FIPS 197), encryption direction only.
+//!
+//! The S-box is computed as the affine transform of the inverse in GF(2^8)
+//! rather than looked up in a table, and all multiplications are done without
+//! data-dependent branches or memory accesses, so the running time does not
+//! depend on the key or the data.
+
+/// Reduction polynomial of GF(2^8), `x^8 + x^4 + x^3 + x + 1`, without the `x^8` term.
+const REDUCTION: u8 = 0x1b;
+
+/// Multiply by `x` in GF(2^8).
+fn xtime(a: u8) -> u8 {
+    (a << 1) ^ (0u8.wrapping_sub(a >> 7) & REDUCTION)
+}
+
+/// Multiply in GF(2^8).
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        a = xtime(a);
+        b >>= 1;
+    }
+    product
+}
+
+/// The AES S-box: the multiplicative inverse `a^254`, followed by the affine transform.
+fn sub_byte(a: u8) -> u8 {
+    let a2 = gf_mul(a, a);
+    let a3 = gf_mul(a2, a);
+    let a6 = gf_mul(a3, a3);
+    let a12 = gf_mul(a6, a6);
+    let a15 = gf_mul(a12, a3);
+    let a30 = gf_mul(a15, a15);
+    let a60 = gf_mul(a30, a30);
+    let a120 = gf_mul(a60, a60);
+    let a240 = gf_mul(a120, a120);
+    let a252 = gf_mul(a240, a12);
+    let inverse = gf_mul(a252, a2);
+
+    inverse
+        ^ inverse.rotate_left(1)
+        ^ inverse.rotate_left(2)
+        ^ inverse.rotate_left(3)
+        ^ inverse.rotate_left(4)
+        ^ 0x63
+}
+
+/// An expanded AES-128, AES-192 or AES-256 key.
+#[derive(Clone)]
+pub(crate) struct Aes {
+    round_keys: [[u8; 16]; 15],
+    rounds: usize,
+}
+
+impl Aes {
+    /// Expand a 16, 24 or 32 byte key. Returns `None` for any other length.
+    pub(crate) fn new(key: &[u8]) -> Option<Self> {
+        let nk = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return None,
+        };
+        let rounds = nk + 6;
+
+        let mut words = [[0u8; 4]; 60];
+        for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+            word.copy_from_slice(chunk);
+        }
+        let mut rcon = 1u8;
+        for i in nk..4 * (rounds + 1) {
+            let mut temp = words[i - 1];
+            if i % nk == 0 {
+                temp = [
+                    sub_byte(temp[1]) ^ rcon,
+                    sub_byte(temp[2]),
+                    sub_byte(temp[3]),
+                    sub_byte(temp[0]),
+                ];
+                rcon = xtime(rcon);
+            } else if nk > 6 && i % nk == 4 {
+                temp = temp.map(sub_byte);
+            }
+            for (byte, prev) in temp.iter_mut().zip(words[i - nk]) {
+                *byte ^= prev;
+            }
+            words[i] = temp;
+        }
+
+        let mut round_keys = [[0u8; 16]; 15];
+        for (round_key, round_words) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+            for (bytes, word) in round_key.chunks_exact_mut(4).zip(round_words) {
+                bytes.copy_from_slice(word);
+            }
+        }
+        Some(Self { round_keys, rounds })
+    }
+
+    /// Encrypt one block in place.
+    pub(crate) fn encrypt_block(&self, block: &mut [u8; 16]) {
+        add_round_key(block, &self.round_keys[0]);
+        for round_key in &self.round_keys[1..self.rounds] {
+            sub_bytes(block);
+            shift_rows(block);
+            mix_columns(block);
+            add_round_key(block, round_key);
+        }
+        sub_bytes(block);
+        shift_rows(block);
+        add_round_key(block, &self.round_keys[self.rounds]);
+    }
+}
+
+fn add_round_key(state: &mut [u8; 16], round_key: &[u8; 16]) {
+    for (byte, key) in state.iter_mut().zip(round_key) {
+        *byte ^= key;
+    }
+}
+
+fn sub_bytes(state: &mut [u8; 16]) {
+    for byte in state {
+        *byte = sub_byte(*byte);
+    }
+}
+
+/// Rotate row `r` left by `r` positions. The state is stored column by column.
+fn shift_rows(state: &mut [u8; 16]) {
+    let old = *state;
+    for c in 0..4 {
+        for r in 1..4 {
+            state[4 * c + r] = old[4 * ((c + r) % 4) + r];
+        }
+    }
+}
+
+fn mix_columns(state: &mut [u8; 16]) {
+    for column in state.chunks_exact_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        let all = a0 ^ a1 ^ a2 ^ a3;
+        column[0] ^= all ^ xtime(a0 ^ a1);
+        column[1] ^= all ^ xtime(a1 ^ a2);
+        column[2] ^= all ^ xtime(a2 ^ a3);
+        column[3] ^= all ^ xtime(a3 ^ a0);
+    }
+}
@@ -0,0 +1,171 @@
+//! Software AES-CMAC implementation (NIST SP 800-38B, RFC 4493).
+
+use super::aes::Aes;
+use crate::key::KeyHandle;
+use crate::mac::{self, AesCmac, ErrorKind, ErrorType, Mac, MacAlgorithm, MacInit};
+
+const BLOCK_SIZE: usize = 16;
+
+/// Multiply by `x` in GF(2^128), the subkey derivation of SP 800-38B.
+fn double(block: &[u8; BLOCK_SIZE]) -> [u8; BLOCK_SIZE] {
+    let mut out = [0u8; BLOCK_SIZE];
+    for i in 0..BLOCK_SIZE - 1 {
+        out[i] = (block[i] << 1) | (block[i + 1] >> 7);
+    }
+    out[BLOCK_SIZE - 1] = (block[BLOCK_SIZE - 1] << 1) ^ (0u8.wrapping_sub(block[0] >> 7) & 0x87);
+    out
+}
+
+/// Software AES-CMAC engine.
+///
+/// The engine holds no state of its own; every call to `init` returns an
+/// independent context.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct AesCmacEngine;
+
+impl ErrorType for AesCmacEngine {
+    type Error = ErrorKind;
+}
+
+impl MacInit<AesCmac> for AesCmacEngine {
+    type OpContext<'a> = AesCmacContext;
+
+    fn init(&mut self, _init_params: AesCmac) -> Result<Self::OpContext<'_>, Self::Error> {
+        Ok(AesCmacContext::new())
+    }
+}
+
+/// An AES-CMAC computation.
+///
+/// A key must be set before any data is authenticated. Only
+/// [`KeyHandle::Raw`] keys are accepted. Finalizing the
+/// computation starts a new message with the same key.
+#[derive(Clone)]
+pub struct AesCmacContext {
+    cipher: Option<Aes>,
+    k1: [u8; BLOCK_SIZE],
+    k2: [u8; BLOCK_SIZE],
+    /// Chaining value over all complete blocks processed so far.
+    state: [u8; BLOCK_SIZE],
+    /// The last, possibly complete, block. It is only processed once more
+    /// data follows, since the final block is masked with a subkey.
+    buffer: [u8; BLOCK_SIZE],
+    buffered: usize,
+}
+
+impl AesCmacContext {
+    /// Create a context without a key.
+    pub const fn new() -> Self {
+        Self {
+            cipher: None,
+            k1: [0; BLOCK_SIZE],
+            k2: [0; BLOCK_SIZE],
+            state: [0; BLOCK_SIZE],
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+        }
+    }
+
+    fn cipher(&self) -> Result<&Aes, ErrorKind> {
+        self.cipher.as_ref().ok_or(ErrorKind::NotInitialized)
+    }
+
+    fn restart(&mut self) {
+        self.state = [0; BLOCK_SIZE];
+        self.buffer = [0; BLOCK_SIZE];
+        self.buffered = 0;
+    }
+
+    fn compute(&mut self) -> Result<[u8; BLOCK_SIZE], ErrorKind> {
+        self.cipher()?;
+        let subkey = if self.buffered == BLOCK_SIZE {
+            self.k1
+        } else {
+            self.buffer[self.buffered] = 0x80;
+            self.buffer[self.buffered + 1..].fill(0);
+            self.k2
+        };
+
+        let mut tag = self.state;
+        for ((byte, data), key) in tag.iter_mut().zip(&self.buffer).zip(subkey) {
+            *byte ^= data ^ key;
+        }
+        self.cipher()?.encrypt_block(&mut tag);
+
+        self.restart();
+        Ok(tag)
+    }
+}
+
+impl Default for AesCmacContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorType for AesCmacContext {
+    type Error = ErrorKind;
+}
+
+impl Mac for AesCmacContext {
+    type Algorithm = AesCmac;
+
+    fn set_key(&mut self, key: KeyHandle<'_>) -> Result<(), Self::Error> {
+        let key = key.as_raw().ok_or(ErrorKind::UnsupportedKeyHandle)?;
+        if !AesCmac::KEY_SIZE.contains(key.len()) {
+            return Err(ErrorKind::InvalidKeySize);
+        }
+        let cipher = Aes::new(key).ok_or(ErrorKind::InvalidKeySize)?;
+
+        let mut l = [0u8; BLOCK_SIZE];
+        cipher.encrypt_block(&mut l);
+        self.k1 = double(&l);
+        self.k2 = double(&self.k1);
+        self.cipher = Some(cipher);
+        self.restart();
+        Ok(())
+    }
+
+    fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+        let Self {
+            cipher,
+            state,
+            buffer,
+            buffered,
+            ..
+        } = self;
+        let cipher = cipher.as_ref().ok_or(ErrorKind::NotInitialized)?;
+        for byte in input {
+            if *buffered == BLOCK_SIZE {
+                for (state, data) in state.iter_mut().zip(buffer.iter()) {
+                    *state ^= data;
+                }
+                cipher.encrypt_block(state);
+                *buffered = 0;
+            }
+            buffer[*buffered] = *byte;
+            *buffered += 1;
+        }
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), Self::Error> {
+        self.cipher()?;
+        self.restart();
+        Ok(())
+    }
+
+    fn finalize(&mut self, out: &mut [u8]) -> Result<(), Self::Error> {
+        if out.len() < BLOCK_SIZE {
+            return Err(ErrorKind::InvalidOutputSize);
+        }
+        let tag = self.compute()?;
+        out[..BLOCK_SIZE].copy_from_slice(&tag);
+        Ok(())
+    }
+
+    fn verify(&mut self, tag: &[u8]) -> Result<(), Self::Error> {
+        let expected = self.compute()?;
+        mac::verify_tag(&expected, tag, AesCmac::MIN_TAG_SIZE)
+    }
+}
@@ -0,0 +1,280 @@
+//! Software ECDSA over P-256 and P-384, backed by the RustCrypto `p256` and
+//! `p384` crates.
+//!
+//! Signing does not branch on secret values: nonces come from the
+//! constant-time [`rfc6979`](crate::rfc6979) generator or from rejection
+//! sampling, and the curve crates multiply and invert scalars in constant time.
+//! Verification only handles public data and uses variable-time arithmetic.
+
+use core::marker::PhantomData;
+
+use ecdsa_core::elliptic_curve::PrimeField;
+use ecdsa_core::hazmat::{bits2field, SignPrimitive};
+use ecdsa_core::signature::hazmat::PrehashVerifier;
+use rand_core::{CryptoRng, RngCore};
+
+use crate::digest::{
+    Digest, DigestAlgorithm, Sha2_224, Sha2_256, Sha2_384, Sha2_512, Sha3_224, Sha3_256, Sha3_384,
+    Sha3_512,
+};
+use crate::ecdsa::encoding::{self, Sec1Encoding, Sec1Point, MAX_SCALAR_SIZE};
+use crate::ecdsa::{
+    EcdsaCurve, EcdsaKeyGen, EcdsaSign, EcdsaSignWithNonce, EcdsaTypes, EcdsaValidate, EcdsaVerify,
+    Error as _, ErrorKind, ErrorType, HashMarker, NonceMode, NonceModes, P256, P384,
+};
+use crate::hmac::HmacContext;
+use crate::key::Validated;
+use crate::rfc6979::NonceGenerator;
+use crate::sw::sha2::{Sha224Context, Sha256Context, Sha384Context, Sha512Context};
+use crate::sw::sha3::{Sha3_224Context, Sha3_256Context, Sha3_384Context, Sha3_512Context};
+
+/// Software ECDSA engine for the curve `C`, [`P256`] or [`P384`].
+///
+/// Key generation and [`NonceMode::Random`] draw from the random number
+/// generator `R`. [`EcdsaSign::sign`] uses [`NonceMode::Deterministic`].
+///
+/// Deterministic and hedged nonces are derived as in RFC 6979, with HMAC over
+/// the hash that produced the message hash. That hash must be one of the SHA-2
+/// or SHA-3 functions in [`digest`](crate::digest); other hashes can only be
+/// signed with [`NonceMode::Random`], and fail with
+/// [`ErrorKind::UnsupportedNonceMode`] otherwise.
+pub struct EcdsaEngine<C, R> {
+    rng: R,
+    _curve: PhantomData<C>,
+}
+
+impl<C, R: RngCore + CryptoRng> EcdsaEngine<C, R> {
+    pub fn new(rng: R) -> Self {
+        Self {
+            rng,
+            _curve: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped random number generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<C, R> ErrorType for EcdsaEngine<C, R> {
+    type Error = ErrorKind;
+}
+
+/// Derives RFC 6979 nonce candidates and hands each to `sign` until it
+/// produces a signature.
+///
+/// The HMAC is computed with the hash that produced `message_hash`. Hashes
+/// without a software implementation fail with
+/// [`ErrorKind::UnsupportedNonceMode`].
+fn sign_rfc6979<C: EcdsaCurve, H: HashMarker, S>(
+    private_key: &[u8],
+    message_hash: &[u8],
+    extra_entropy: &[u8],
+    sign: impl FnMut(&[u8]) -> Option<S>,
+) -> Result<S, ErrorKind> {
+    macro_rules! sign_with {
+        ($($algorithm:ident => $context:ident),*) => {
+            $(
+                if H::OID == $algorithm::OID {
+                    return sign_with_hmac::<C, _, _>(
+                        $context::new(),
+                        private_key,
+                        message_hash,
+                        extra_entropy,
+                        sign,
+                    );
+                }
+            )*
+        };
+    }
+
+    sign_with!(
+        Sha2_224 => Sha224Context,
+        Sha2_256 => Sha256Context,
+        Sha2_384 => Sha384Context,
+        Sha2_512 => Sha512Context,
+        Sha3_224 => Sha3_224Context,
+        Sha3_256 => Sha3_256Context,
+        Sha3_384 => Sha3_384Context,
+        Sha3_512 => Sha3_512Context
+    );
+    Err(ErrorKind::UnsupportedNonceMode)
+}
+
+fn sign_with_hmac<C: EcdsaCurve, D: Digest, S>(
+    digest: D,
+    private_key: &[u8],
+    message_hash: &[u8],
+    extra_entropy: &[u8],
+    mut sign: impl FnMut(&[u8]) -> Option<S>,
+) -> Result<S, ErrorKind> {
+    let mut mac = HmacContext::new(digest);
+    let mut generator =
+        NonceGenerator::<_, C>::new(&mut mac, private_key, message_hash, extra_entropy)
+            .map_err(|e| e.kind())?;
+    let mut k = [0u8; MAX_SCALAR_SIZE];
+    let result = loop {
+        if let Err(e) = generator.next_k(&mut k) {
+            break Err(e.kind());
+        }
+        if let Some(signature) = sign(&k[..C::SCALAR_SIZE]) {
+            break Ok(signature);
+        }
+    };
+    k.fill(0);
+    result
+}
+
+macro_rules! sw_ecdsa {
+    ($curve:ident, $krate:ident, $nist:ident) => {
+        impl<R> EcdsaTypes for EcdsaEngine<$curve, R> {
+            type PrivateKey = $krate::ecdsa::SigningKey;
+            type PublicKey = $krate::ecdsa::VerifyingKey;
+            type Signature = $krate::ecdsa::Signature;
+            type Curve = $curve;
+        }
+
+        impl<R: RngCore + CryptoRng> EcdsaKeyGen for EcdsaEngine<$curve, R> {
+            fn generate_key_pair(
+                &mut self,
+                _curve: &Self::Curve,
+            ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error> {
+                let private_key = $krate::ecdsa::SigningKey::random(&mut self.rng);
+                let public_key = *private_key.verifying_key();
+                Ok((private_key, public_key))
+            }
+        }
+
+        impl<R: RngCore + CryptoRng> EcdsaSign for EcdsaEngine<$curve, R> {
+            type PrivateKey = $krate::ecdsa::SigningKey;
+            type Curve = $curve;
+            type Signature = $krate::ecdsa::Signature;
+
+            fn sign<H: HashMarker>(
+                &mut self,
+                curve: &Self::Curve,
+                private_key: &Self::PrivateKey,
+                message_hash: impl AsRef<[u8]>,
+            ) -> Result<Self::Signature, Self::Error> {
+                self.sign_with_nonce::<H>(
+                    curve,
+                    private_key,
+                    message_hash,
+                    NonceMode::Deterministic,
+                )
+            }
+        }
+
+        impl<R: RngCore + CryptoRng> EcdsaSignWithNonce for EcdsaEngine<$curve, R> {
+            const NONCE_MODES: NonceModes = NonceModes {
+                random: true,
+                deterministic: true,
+                hedged: true,
+            };
+
+            fn sign_with_nonce<H: HashMarker>(
+                &mut self,
+                _curve: &Self::Curve,
+                private_key: &Self::PrivateKey,
+                message_hash: impl AsRef<[u8]>,
+                nonce: NonceMode<'_>,
+            ) -> Result<Self::Signature, Self::Error> {
+                let message_hash = message_hash.as_ref();
+                if message_hash.len() != H::OUTPUT_SIZE {
+                    return Err(ErrorKind::SigningError);
+                }
+                let z = bits2field::<$krate::$nist>(message_hash)
+                    .map_err(|_| ErrorKind::SigningError)?;
+                let d = private_key.as_nonzero_scalar();
+                let sign_with = |k: $krate::Scalar| {
+                    d.try_sign_prehashed(k, &z)
+                        .ok()
+                        .map(|(signature, _)| signature)
+                };
+
+                match nonce {
+                    NonceMode::Random => loop {
+                        let k = $krate::NonZeroScalar::random(&mut self.rng);
+                        if let Some(signature) = sign_with(*k) {
+                            return Ok(signature);
+                        }
+                    },
+                    NonceMode::Deterministic | NonceMode::Hedged { .. } => {
+                        let extra_entropy = match nonce {
+                            NonceMode::Hedged { extra_entropy } => extra_entropy,
+                            _ => &[],
+                        };
+                        let mut x = private_key.to_bytes();
+                        let result =
+                            sign_rfc6979::<$curve, H, _>(&x, message_hash, extra_entropy, |k| {
+                                let mut bytes = $krate::FieldBytes::default();
+                                bytes.copy_from_slice(k);
+                                Option::from($krate::Scalar::from_repr(bytes)).and_then(sign_with)
+                            });
+                        x.fill(0);
+                        result
+                    }
+                }
+            }
+        }
+
+        /// Decodes through [`Sec1Point::parse`], then checks that the point is
+        /// on the curve.
+        impl Sec1Encoding for $krate::ecdsa::VerifyingKey {
+            type Curve = $curve;
+
+            fn to_sec1(&self, compress: bool, out: &mut [u8]) -> Result<usize, encoding::Error> {
+                let point = self.to_encoded_point(compress);
+                Sec1Point::parse::<$curve>(point.as_bytes())?.encode::<$curve>(out)
+            }
+
+            fn from_sec1(bytes: &[u8]) -> Result<Self, encoding::Error> {
+                Sec1Point::parse::<$curve>(bytes)?;
+                Self::from_sec1_bytes(bytes).map_err(|_| encoding::Error::NotOnCurve)
+            }
+        }
+
+        /// Verifying keys can only hold a point on the curve other than the
+        /// identity. The check re-encodes the point through [`Sec1Encoding`].
+        impl<R> EcdsaValidate for EcdsaEngine<$curve, R> {
+            type PublicKey = $krate::ecdsa::VerifyingKey;
+            type Curve = $curve;
+
+            fn check_public_key(
+                &mut self,
+                _curve: &Self::Curve,
+                public_key: &Self::PublicKey,
+            ) -> Result<(), Self::Error> {
+                let mut point = [0; <$curve as EcdsaCurve>::POINT_SIZE];
+                public_key
+                    .to_sec1(false, &mut point)
+                    .map_err(|_| ErrorKind::InvalidPublicKey)?;
+                Ok(())
+            }
+        }
+
+        impl<R> EcdsaVerify for EcdsaEngine<$curve, R> {
+            type Signature = $krate::ecdsa::Signature;
+
+            fn verify<H: HashMarker>(
+                &mut self,
+                _curve: &Self::Curve,
+                public_key: &Validated<Self::PublicKey>,
+                message_hash: impl AsRef<[u8]>,
+                signature: &Self::Signature,
+            ) -> Result<(), Self::Error> {
+                let message_hash = message_hash.as_ref();
+                if message_hash.len() != H::OUTPUT_SIZE {
+                    return Err(ErrorKind::InvalidSignature);
+                }
+                public_key
+                    .verify_prehash(message_hash, signature)
+                    .map_err(|_| ErrorKind::InvalidSignature)
+            }
+        }
+    };
+}
+
+sw_ecdsa!(P256, p256, NistP256);
+sw_ecdsa!(P384, p384, NistP384);
@@ -0,0 +1,173 @@
+//! Software Ed25519 and Ed25519ph signatures backed by `ed25519-dalek`.
+//!
+//! Ed25519ctx is not supported: [`EddsaSign::sign`] and [`EddsaVerify::verify`]
+//! fail with [`ErrorKind::InvalidContext`] when given a non-empty context.
+//! Verification rejects small-order public keys and non-canonical signatures.
+
+use digest::consts::U64;
+use digest::{FixedOutput, HashMarker, Output, OutputSizeUser, Update};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use rand_core::{CryptoRng, RngCore};
+
+use crate::eddsa::{
+    Ed25519, EddsaCurve, EddsaKeyGen, EddsaSign, EddsaTypes, EddsaVerify, ErrorKind, ErrorType,
+    MAX_CONTEXT_SIZE,
+};
+
+/// Software Ed25519 engine.
+///
+/// Key generation draws from the random number generator `R`.
+pub struct Ed25519Engine<R> {
+    rng: R,
+}
+
+impl<R: RngCore + CryptoRng> Ed25519Engine<R> {
+    pub fn new(rng: R) -> Self {
+        Self { rng }
+    }
+
+    /// Returns the wrapped random number generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+/// A message prehash computed by the caller, handed to `ed25519-dalek` as the
+/// finished digest it expects.
+struct Prehash([u8; 64]);
+
+impl Default for Prehash {
+    fn default() -> Self {
+        Self([0; 64])
+    }
+}
+
+impl Prehash {
+    fn new(prehash: &[u8]) -> Result<Self, ErrorKind> {
+        prehash
+            .try_into()
+            .map(Self)
+            .map_err(|_| ErrorKind::InvalidPrehashSize)
+    }
+}
+
+impl HashMarker for Prehash {}
+
+impl OutputSizeUser for Prehash {
+    type OutputSize = U64;
+}
+
+impl Update for Prehash {
+    fn update(&mut self, _data: &[u8]) {
+        // `sign_prehashed` and `verify_prehashed_strict` only finalize the
+        // digest they are given, so nothing is ever fed to the prehash.
+    }
+}
+
+impl FixedOutput for Prehash {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        out.copy_from_slice(&self.0);
+    }
+}
+
+fn check_context(context: &[u8]) -> Result<(), ErrorKind> {
+    if context.len() > MAX_CONTEXT_SIZE {
+        return Err(ErrorKind::InvalidContext);
+    }
+    Ok(())
+}
+
+impl<R> ErrorType for Ed25519Engine<R> {
+    type Error = ErrorKind;
+}
+
+impl<R> EddsaTypes for Ed25519Engine<R> {
+    type PrivateKey = SigningKey;
+    type PublicKey = VerifyingKey;
+    type Signature = Signature;
+    type Curve = Ed25519;
+}
+
+impl<R: RngCore + CryptoRng> EddsaKeyGen for Ed25519Engine<R> {
+    fn generate_key_pair(
+        &mut self,
+        _curve: &Self::Curve,
+    ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error> {
+        let mut seed = [0u8; <Ed25519 as EddsaCurve>::PRIVATE_KEY_SIZE];
+        self.rng
+            .try_fill_bytes(&mut seed)
+            .map_err(|_| ErrorKind::KeyGenError)?;
+        let private_key = SigningKey::from_bytes(&seed);
+        seed.fill(0);
+        let public_key = private_key.verifying_key();
+        Ok((private_key, public_key))
+    }
+}
+
+impl<R> EddsaSign for Ed25519Engine<R> {
+    type PrivateKey = SigningKey;
+    type Curve = Ed25519;
+    type Signature = Signature;
+
+    fn sign(
+        &mut self,
+        _curve: &Self::Curve,
+        private_key: &Self::PrivateKey,
+        message: impl AsRef<[u8]>,
+        context: &[u8],
+    ) -> Result<Self::Signature, Self::Error> {
+        if !context.is_empty() {
+            return Err(ErrorKind::InvalidContext);
+        }
+        Ok(private_key.sign(message.as_ref()))
+    }
+
+    fn sign_prehashed(
+        &mut self,
+        _curve: &Self::Curve,
+        private_key: &Self::PrivateKey,
+        prehash: impl AsRef<[u8]>,
+        context: &[u8],
+    ) -> Result<Self::Signature, Self::Error> {
+        check_context(context)?;
+        private_key
+            .sign_prehashed(Prehash::new(prehash.as_ref())?, Some(context))
+            .map_err(|_| ErrorKind::SigningError)
+    }
+}
+
+impl<R> EddsaVerify for Ed25519Engine<R> {
+    type PublicKey = VerifyingKey;
+    type Curve = Ed25519;
+    type Signature = Signature;
+
+    fn verify(
+        &mut self,
+        _curve: &Self::Curve,
+        public_key: &Self::PublicKey,
+        message: impl AsRef<[u8]>,
+        context: &[u8],
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error> {
+        if !context.is_empty() {
+            return Err(ErrorKind::InvalidContext);
+        }
+        public_key
+            .verify_strict(message.as_ref(), signature)
+            .map_err(|_| ErrorKind::InvalidSignature)
+    }
+
+    fn verify_prehashed(
+        &mut self,
+        _curve: &Self::Curve,
+        public_key: &Self::PublicKey,
+        prehash: impl AsRef<[u8]>,
+        context: &[u8],
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error> {
+        check_context(context)?;
+        public_key
+            .verify_prehashed_strict(Prehash::new(prehash.as_ref())?, Some(context), signature)
+            .map_err(|_| ErrorKind::InvalidSignature)
+    }
+}
@@ -0,0 +1,26 @@
+//! Software implementations of the peripheral traits.
+//!
+//! These serve as a fallback on targets without the corresponding hardware and
+//! as a reference to compare hardware backends against. Each implementation is
+//! behind its own cargo feature.
+
+#[cfg(feature = "sw-sha2")]
+pub mod sha2;
+
+#[cfg(feature = "sw-sha3")]
+pub mod sha3;
+
+#[cfg(feature = "sw-aes-cmac")]
+mod aes;
+
+#[cfg(feature = "sw-aes-cmac")]
+pub mod cmac;
+
+#[cfg(feature = "sw-x25519")]
+pub mod x25519;
+
+#[cfg(feature = "sw-ed25519")]
+pub mod ed25519;
+
+#[cfg(feature = "sw-ecdsa")]
+pub mod ecdsa;
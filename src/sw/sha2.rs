@@ -0,0 +1,475 @@
+//! Software SHA-2 implementation.
+//!
+//! Provides SHA-224, SHA-256, SHA-384 and SHA-512 as described in FIPS 180-4.
+
+use crate::digest::{
+    Digest, DigestAlgorithm, DigestInit, DigestRestore, DigestSave, ErrorKind, ErrorType,
+    Sha2_224, Sha2_256, Sha2_384, Sha2_512,
+};
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const SHA224_IV: [u32; 8] = [
+    0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7, 0xbefa4fa4,
+];
+
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+const SHA384_IV: [u64; 8] = [
+    0xcbbb9d5dc1059ed8, 0x629a292a367cd507, 0x9159015a3070dd17, 0x152fecd8f70e5939,
+    0x67332667ffc00b31, 0x8eb44a8768581511, 0xdb0c2e0d64f98fa7, 0x47b5481dbefa4fa4,
+];
+
+const SHA512_IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179,
+];
+
+/// SHA-256 compression state shared by the 32-bit word variants.
+#[derive(Clone)]
+struct Core256 {
+    iv: [u32; 8],
+    h: [u32; 8],
+    buffer: [u8; 64],
+    /// Number of message bytes absorbed so far.
+    length: u64,
+}
+
+impl Core256 {
+    /// Size of a saved state: tag, chaining value, length and pending block.
+    const STATE_SIZE: usize = 1 + 32 + 8 + 64;
+
+    const fn new(iv: [u32; 8]) -> Self {
+        Self {
+            iv,
+            h: iv,
+            buffer: [0; 64],
+            length: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.iv);
+    }
+
+    fn update(&mut self, mut input: &[u8]) -> Result<(), ErrorKind> {
+        // The message length in bits must fit in the 64-bit length field.
+        let length = self
+            .length
+            .checked_add(input.len() as u64)
+            .filter(|length| *length < 1 << 61)
+            .ok_or(ErrorKind::InvalidInputLength)?;
+
+        let pending = (self.length % 64) as usize;
+        if pending > 0 {
+            let take = input.len().min(64 - pending);
+            self.buffer[pending..pending + take].copy_from_slice(&input[..take]);
+            input = &input[take..];
+            if pending + take < 64 {
+                self.length = length;
+                return Ok(());
+            }
+            let block = self.buffer;
+            self.compress(&block);
+        }
+
+        let mut blocks = input.chunks_exact(64);
+        for block in &mut blocks {
+            self.compress(block);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.length = length;
+        Ok(())
+    }
+
+    /// Pad the message and write the big-endian chaining value, truncated to `out.len()`.
+    fn finalize(&mut self, out: &mut [u8]) {
+        let bit_length = self.length * 8;
+        let pending = (self.length % 64) as usize;
+        let mut block = [0u8; 64];
+        block[..pending].copy_from_slice(&self.buffer[..pending]);
+        block[pending] = 0x80;
+        if pending >= 56 {
+            self.compress(&block);
+            block = [0; 64];
+        }
+        block[56..].copy_from_slice(&bit_length.to_be_bytes());
+        self.compress(&block);
+
+        for (chunk, word) in out.chunks_mut(4).zip(self.h) {
+            chunk.copy_from_slice(&word.to_be_bytes()[..chunk.len()]);
+        }
+        self.reset();
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut w = [0u32; 64];
+        for (word, bytes) in w.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.h;
+        for (k, w) in SHA256_K.iter().zip(w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (state, value) in self.h.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    fn save(&self, tag: u8, state: &mut [u8]) -> Result<usize, ErrorKind> {
+        let state = state
+            .get_mut(..Self::STATE_SIZE)
+            .ok_or(ErrorKind::InvalidOutputSize)?;
+        state[0] = tag;
+        for (chunk, word) in state[1..33].chunks_exact_mut(4).zip(self.h) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        state[33..41].copy_from_slice(&self.length.to_be_bytes());
+        state[41..].copy_from_slice(&self.buffer);
+        Ok(Self::STATE_SIZE)
+    }
+
+    fn restore(iv: [u32; 8], tag: u8, state: &[u8]) -> Result<Self, ErrorKind> {
+        if state.len() != Self::STATE_SIZE || state[0] != tag {
+            return Err(ErrorKind::InvalidState);
+        }
+        let mut core = Self::new(iv);
+        for (word, chunk) in core.h.iter_mut().zip(state[1..33].chunks_exact(4)) {
+            *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let mut length = [0u8; 8];
+        length.copy_from_slice(&state[33..41]);
+        core.length = u64::from_be_bytes(length);
+        if core.length >= 1 << 61 {
+            return Err(ErrorKind::InvalidState);
+        }
+        core.buffer.copy_from_slice(&state[41..]);
+        Ok(core)
+    }
+}
+
+/// SHA-512 compression state shared by the 64-bit word variants.
+#[derive(Clone)]
+struct Core512 {
+    iv: [u64; 8],
+    h: [u64; 8],
+    buffer: [u8; 128],
+    /// Number of message bytes absorbed so far.
+    length: u64,
+}
+
+impl Core512 {
+    /// Size of a saved state: tag, chaining value, length and pending block.
+    const STATE_SIZE: usize = 1 + 64 + 8 + 128;
+
+    const fn new(iv: [u64; 8]) -> Self {
+        Self {
+            iv,
+            h: iv,
+            buffer: [0; 128],
+            length: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.iv);
+    }
+
+    fn update(&mut self, mut input: &[u8]) -> Result<(), ErrorKind> {
+        // Lengths are tracked in bytes; a 64-bit counter is far beyond any
+        // message this implementation will see, but overflow is still rejected.
+        let length = self
+            .length
+            .checked_add(input.len() as u64)
+            .ok_or(ErrorKind::InvalidInputLength)?;
+
+        let pending = (self.length % 128) as usize;
+        if pending > 0 {
+            let take = input.len().min(128 - pending);
+            self.buffer[pending..pending + take].copy_from_slice(&input[..take]);
+            input = &input[take..];
+            if pending + take < 128 {
+                self.length = length;
+                return Ok(());
+            }
+            let block = self.buffer;
+            self.compress(&block);
+        }
+
+        let mut blocks = input.chunks_exact(128);
+        for block in &mut blocks {
+            self.compress(block);
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.length = length;
+        Ok(())
+    }
+
+    /// Pad the message and write the big-endian chaining value, truncated to `out.len()`.
+    fn finalize(&mut self, out: &mut [u8]) {
+        let bit_length = u128::from(self.length) * 8;
+        let pending = (self.length % 128) as usize;
+        let mut block = [0u8; 128];
+        block[..pending].copy_from_slice(&self.buffer[..pending]);
+        block[pending] = 0x80;
+        if pending >= 112 {
+            self.compress(&block);
+            block = [0; 128];
+        }
+        block[112..].copy_from_slice(&bit_length.to_be_bytes());
+        self.compress(&block);
+
+        for (chunk, word) in out.chunks_mut(8).zip(self.h) {
+            chunk.copy_from_slice(&word.to_be_bytes()[..chunk.len()]);
+        }
+        self.reset();
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut w = [0u64; 80];
+        for (word, bytes) in w.iter_mut().zip(block.chunks_exact(8)) {
+            let mut be = [0u8; 8];
+            be.copy_from_slice(bytes);
+            *word = u64::from_be_bytes(be);
+        }
+        for i in 16..80 {
+            let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+            let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.h;
+        for (k, w) in SHA512_K.iter().zip(w) {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(w);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        for (state, value) in self.h.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    fn save(&self, tag: u8, state: &mut [u8]) -> Result<usize, ErrorKind> {
+        let state = state
+            .get_mut(..Self::STATE_SIZE)
+            .ok_or(ErrorKind::InvalidOutputSize)?;
+        state[0] = tag;
+        for (chunk, word) in state[1..65].chunks_exact_mut(8).zip(self.h) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        state[65..73].copy_from_slice(&self.length.to_be_bytes());
+        state[73..].copy_from_slice(&self.buffer);
+        Ok(Self::STATE_SIZE)
+    }
+
+    fn restore(iv: [u64; 8], tag: u8, state: &[u8]) -> Result<Self, ErrorKind> {
+        if state.len() != Self::STATE_SIZE || state[0] != tag {
+            return Err(ErrorKind::InvalidState);
+        }
+        let mut core = Self::new(iv);
+        for (word, chunk) in core.h.iter_mut().zip(state[1..65].chunks_exact(8)) {
+            let mut be = [0u8; 8];
+            be.copy_from_slice(chunk);
+            *word = u64::from_be_bytes(be);
+        }
+        let mut length = [0u8; 8];
+        length.copy_from_slice(&state[65..73]);
+        core.length = u64::from_be_bytes(length);
+        core.buffer.copy_from_slice(&state[73..]);
+        Ok(core)
+    }
+}
+
+/// Software SHA-2 engine.
+///
+/// The engine holds no state of its own; every call to [`DigestInit::init`]
+/// returns an independent context.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Sha2Engine;
+
+impl ErrorType for Sha2Engine {
+    type Error = ErrorKind;
+}
+
+macro_rules! sha2_context {
+    ($(#[$meta:meta])* $name:ident, $algorithm:ident, $core:ident, $iv:ident) => {
+        $(#[$meta])*
+        ///
+        /// Finalizing the computation resets the context, so it can be reused
+        /// for a new message right away.
+        #[derive(Clone)]
+        pub struct $name {
+            core: $core,
+        }
+
+        impl $name {
+            /// Create a context for a new computation.
+            pub const fn new() -> Self {
+                Self {
+                    core: $core::new($iv),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl ErrorType for $name {
+            type Error = ErrorKind;
+        }
+
+        impl Digest for $name {
+            type Algorithm = $algorithm;
+
+            fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+                self.core.update(input)
+            }
+
+            fn reset(&mut self) -> Result<(), Self::Error> {
+                self.core.reset();
+                Ok(())
+            }
+
+            fn finalize(
+                &mut self,
+            ) -> Result<<$algorithm as DigestAlgorithm>::DigestOutput, Self::Error> {
+                let mut out = <$algorithm as DigestAlgorithm>::DigestOutput::default();
+                self.core.finalize(out.as_mut());
+                Ok(out)
+            }
+        }
+
+        impl DigestSave for $name {
+            const SAVED_STATE_SIZE: usize = $core::STATE_SIZE;
+
+            fn save(&mut self, state: &mut [u8]) -> Result<usize, Self::Error> {
+                self.core.save($algorithm::OUTPUT_SIZE as u8, state)
+            }
+        }
+
+        impl DigestInit<$algorithm> for Sha2Engine {
+            type OpContext<'a> = $name;
+
+            fn init(
+                &mut self,
+                _init_params: $algorithm,
+            ) -> Result<Self::OpContext<'_>, Self::Error> {
+                Ok($name::new())
+            }
+        }
+
+        impl DigestRestore<$algorithm> for Sha2Engine {
+            fn restore(
+                &mut self,
+                _init_params: $algorithm,
+                state: &[u8],
+            ) -> Result<Self::OpContext<'_>, Self::Error> {
+                Ok($name {
+                    core: $core::restore($iv, $algorithm::OUTPUT_SIZE as u8, state)?,
+                })
+            }
+        }
+    };
+}
+
+sha2_context!(
+    /// A SHA-224 computation.
+    Sha224Context, Sha2_224, Core256, SHA224_IV
+);
+sha2_context!(
+    /// A SHA-256 computation.
+    Sha256Context, Sha2_256, Core256, SHA256_IV
+);
+sha2_context!(
+    /// A SHA-384 computation.
+    Sha384Context, Sha2_384, Core512, SHA384_IV
+);
+sha2_context!(
+    /// A SHA-512 computation.
+    Sha512Context, Sha2_512, Core512, SHA512_IV
+);
@@ -0,0 +1,378 @@
+//! Software SHA-3 implementation.
+//!
+//! Provides the SHA3-224, SHA3-256, SHA3-384 and SHA3-512 digests as well as
+//! the SHAKE and cSHAKE extendable-output functions, described in FIPS 202 and
+//! NIST SP 800-185.
+
+use crate::digest::{
+    Digest, DigestAlgorithm, DigestInit, ErrorKind, ErrorType, Sha3_224, Sha3_256, Sha3_384,
+    Sha3_512,
+};
+use crate::xof::{CShake128, CShake256, Shake128, Shake256, Xof, XofInit, XofReader};
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// Domain separation suffix of the SHA-3 digests.
+const SHA3_SUFFIX: u8 = 0x06;
+/// Domain separation suffix of SHAKE.
+const SHAKE_SUFFIX: u8 = 0x1f;
+/// Domain separation suffix of cSHAKE with a non-empty function name or customization.
+const CSHAKE_SUFFIX: u8 = 0x04;
+
+/// The Keccak-f[1600] permutation.
+fn keccak_f1600(a: &mut [u64; 25]) {
+    for rc in ROUND_CONSTANTS {
+        let mut c = [0u64; 5];
+        for (x, column) in c.iter_mut().enumerate() {
+            *column = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[5 * y + x] ^= d;
+            }
+        }
+
+        let mut last = a[1];
+        for (rho, pi) in RHO.iter().zip(PI) {
+            let lane = a[pi];
+            a[pi] = last.rotate_left(*rho);
+            last = lane;
+        }
+
+        for y in 0..5 {
+            let mut row = [0u64; 5];
+            row.copy_from_slice(&a[5 * y..5 * y + 5]);
+            for x in 0..5 {
+                a[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        a[0] ^= rc;
+    }
+}
+
+/// Keccak sponge over Keccak-f[1600], operating on bytes.
+#[derive(Clone)]
+pub(crate) struct Sponge {
+    state: [u64; 25],
+    /// Rate in bytes.
+    rate: usize,
+    /// Position within the current block.
+    pos: usize,
+}
+
+impl Sponge {
+    pub(crate) const fn new(rate: usize) -> Self {
+        Self {
+            state: [0; 25],
+            rate,
+            pos: 0,
+        }
+    }
+
+    fn xor_byte(&mut self, pos: usize, byte: u8) {
+        self.state[pos / 8] ^= u64::from(byte) << (8 * (pos % 8));
+    }
+
+    pub(crate) fn absorb(&mut self, input: &[u8]) {
+        for byte in input {
+            self.xor_byte(self.pos, *byte);
+            self.pos += 1;
+            if self.pos == self.rate {
+                keccak_f1600(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    /// Zero-pad the absorbed data to a multiple of the rate.
+    pub(crate) fn fill_block(&mut self) {
+        if self.pos != 0 {
+            keccak_f1600(&mut self.state);
+            self.pos = 0;
+        }
+    }
+
+    /// Apply the domain suffix and padding, and switch to squeezing.
+    pub(crate) fn pad(&mut self, suffix: u8) {
+        self.xor_byte(self.pos, suffix);
+        self.xor_byte(self.rate - 1, 0x80);
+        keccak_f1600(&mut self.state);
+        self.pos = 0;
+    }
+
+    pub(crate) fn squeeze(&mut self, out: &mut [u8]) {
+        for byte in out {
+            if self.pos == self.rate {
+                keccak_f1600(&mut self.state);
+                self.pos = 0;
+            }
+            *byte = (self.state[self.pos / 8] >> (8 * (self.pos % 8))) as u8;
+            self.pos += 1;
+        }
+    }
+}
+
+/// `left_encode` from NIST SP 800-185, returning the encoding and its length.
+pub(crate) fn left_encode(value: u64) -> ([u8; 9], usize) {
+    let bytes = value.to_be_bytes();
+    let skip = (value.leading_zeros() as usize / 8).min(7);
+    let mut encoded = [0u8; 9];
+    encoded[0] = (8 - skip) as u8;
+    encoded[1..9 - skip].copy_from_slice(&bytes[skip..]);
+    (encoded, 9 - skip)
+}
+
+/// Absorb `encode_string(s)` from NIST SP 800-185.
+pub(crate) fn absorb_encoded_string(sponge: &mut Sponge, s: &[u8]) {
+    let (encoded, len) = left_encode(s.len() as u64 * 8);
+    sponge.absorb(&encoded[..len]);
+    sponge.absorb(s);
+}
+
+/// Sponge primed with the cSHAKE prefix `bytepad(encode_string(N) || encode_string(S), rate)`.
+pub(crate) fn cshake_sponge(rate: usize, function_name: &[u8], customization: &[u8]) -> Sponge {
+    let mut sponge = Sponge::new(rate);
+    let (encoded, len) = left_encode(rate as u64);
+    sponge.absorb(&encoded[..len]);
+    absorb_encoded_string(&mut sponge, function_name);
+    absorb_encoded_string(&mut sponge, customization);
+    sponge.fill_block();
+    sponge
+}
+
+/// Software SHA-3 engine.
+///
+/// The engine holds no state of its own; every call to `init` returns an
+/// independent context.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Sha3Engine;
+
+impl ErrorType for Sha3Engine {
+    type Error = ErrorKind;
+}
+
+macro_rules! sha3_context {
+    ($(#[$meta:meta])* $name:ident, $algorithm:ident) => {
+        $(#[$meta])*
+        ///
+        /// Finalizing the computation resets the context, so it can be reused
+        /// for a new message right away.
+        #[derive(Clone)]
+        pub struct $name {
+            sponge: Sponge,
+        }
+
+        impl $name {
+            /// Create a context for a new computation.
+            pub const fn new() -> Self {
+                Self {
+                    sponge: Sponge::new(200 - 2 * $algorithm::OUTPUT_SIZE),
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl ErrorType for $name {
+            type Error = ErrorKind;
+        }
+
+        impl Digest for $name {
+            type Algorithm = $algorithm;
+
+            fn update(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+                self.sponge.absorb(input);
+                Ok(())
+            }
+
+            fn reset(&mut self) -> Result<(), Self::Error> {
+                *self = Self::new();
+                Ok(())
+            }
+
+            fn finalize(
+                &mut self,
+            ) -> Result<<$algorithm as DigestAlgorithm>::DigestOutput, Self::Error> {
+                let mut out = <$algorithm as DigestAlgorithm>::DigestOutput::default();
+                self.sponge.pad(SHA3_SUFFIX);
+                self.sponge.squeeze(out.as_mut());
+                *self = Self::new();
+                Ok(out)
+            }
+        }
+
+        impl DigestInit<$algorithm> for Sha3Engine {
+            type OpContext<'a> = $name;
+
+            fn init(
+                &mut self,
+                _init_params: $algorithm,
+            ) -> Result<Self::OpContext<'_>, Self::Error> {
+                Ok($name::new())
+            }
+        }
+    };
+}
+
+sha3_context!(
+    /// A SHA3-224 computation.
+    Sha3_224Context, Sha3_224
+);
+sha3_context!(
+    /// A SHA3-256 computation.
+    Sha3_256Context, Sha3_256
+);
+sha3_context!(
+    /// A SHA3-384 computation.
+    Sha3_384Context, Sha3_384
+);
+sha3_context!(
+    /// A SHA3-512 computation.
+    Sha3_512Context, Sha3_512
+);
+
+/// A SHAKE or cSHAKE computation in its absorbing phase.
+#[derive(Clone)]
+pub struct ShakeContext {
+    sponge: Sponge,
+    /// Sponge state after absorbing any cSHAKE prefix, restored on reset.
+    initial: Sponge,
+    suffix: u8,
+}
+
+impl ShakeContext {
+    /// Create a SHAKE128 computation.
+    pub const fn shake128() -> Self {
+        Self::shake(168)
+    }
+
+    /// Create a SHAKE256 computation.
+    pub const fn shake256() -> Self {
+        Self::shake(136)
+    }
+
+    /// Create a cSHAKE128 computation.
+    pub fn cshake128(params: CShake128<'_>) -> Self {
+        Self::cshake(168, params.function_name, params.customization)
+    }
+
+    /// Create a cSHAKE256 computation.
+    pub fn cshake256(params: CShake256<'_>) -> Self {
+        Self::cshake(136, params.function_name, params.customization)
+    }
+
+    const fn shake(rate: usize) -> Self {
+        Self {
+            sponge: Sponge::new(rate),
+            initial: Sponge::new(rate),
+            suffix: SHAKE_SUFFIX,
+        }
+    }
+
+    fn cshake(rate: usize, function_name: &[u8], customization: &[u8]) -> Self {
+        if function_name.is_empty() && customization.is_empty() {
+            return Self::shake(rate);
+        }
+        let sponge = cshake_sponge(rate, function_name, customization);
+        Self {
+            initial: sponge.clone(),
+            sponge,
+            suffix: CSHAKE_SUFFIX,
+        }
+    }
+}
+
+impl ErrorType for ShakeContext {
+    type Error = ErrorKind;
+}
+
+impl Xof for ShakeContext {
+    type Reader = ShakeReader;
+
+    fn absorb(&mut self, input: &[u8]) -> Result<(), Self::Error> {
+        self.sponge.absorb(input);
+        Ok(())
+    }
+
+    fn reset(&mut self) -> Result<(), Self::Error> {
+        self.sponge = self.initial.clone();
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Self::Reader, Self::Error> {
+        self.sponge.pad(self.suffix);
+        Ok(ShakeReader {
+            sponge: self.sponge,
+        })
+    }
+}
+
+/// A SHAKE or cSHAKE computation in its squeezing phase.
+#[derive(Clone)]
+pub struct ShakeReader {
+    sponge: Sponge,
+}
+
+impl ErrorType for ShakeReader {
+    type Error = ErrorKind;
+}
+
+impl XofReader for ShakeReader {
+    fn squeeze(&mut self, out: &mut [u8]) -> Result<(), Self::Error> {
+        self.sponge.squeeze(out);
+        Ok(())
+    }
+}
+
+impl XofInit<Shake128> for Sha3Engine {
+    type OpContext<'a> = ShakeContext;
+
+    fn init(&mut self, _init_params: Shake128) -> Result<Self::OpContext<'_>, Self::Error> {
+        Ok(ShakeContext::shake128())
+    }
+}
+
+impl XofInit<Shake256> for Sha3Engine {
+    type OpContext<'a> = ShakeContext;
+
+    fn init(&mut self, _init_params: Shake256) -> Result<Self::OpContext<'_>, Self::Error> {
+        Ok(ShakeContext::shake256())
+    }
+}
+
+impl XofInit<CShake128<'_>> for Sha3Engine {
+    type OpContext<'a> = ShakeContext;
+
+    fn init(&mut self, init_params: CShake128<'_>) -> Result<Self::OpContext<'_>, Self::Error> {
+        Ok(ShakeContext::cshake128(init_params))
+    }
+}
+
+impl XofInit<CShake256<'_>> for Sha3Engine {
+    type OpContext<'a> = ShakeContext;
+
+    fn init(&mut self, init_params: CShake256<'_>) -> Result<Self::OpContext<'_>, Self::Error> {
+        Ok(ShakeContext::cshake256(init_params))
+    }
+}
@@ -0,0 +1,79 @@
+//! Software X25519 key agreement backed by `x25519-dalek`.
+
+use rand_core::{CryptoRng, RngCore};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::ecdh::{Ecdh, EcdhKeyGen, EcdhTypes, EcdhValidate, ErrorKind, ErrorType, X25519};
+use crate::key::Validated;
+
+/// Software X25519 engine.
+///
+/// Key generation draws from the random number generator `R`. The shared
+/// secret is returned as bytes.
+pub struct X25519Engine<R> {
+    rng: R,
+}
+
+impl<R: RngCore + CryptoRng> X25519Engine<R> {
+    pub fn new(rng: R) -> Self {
+        Self { rng }
+    }
+
+    /// Returns the wrapped random number generator.
+    pub fn into_inner(self) -> R {
+        self.rng
+    }
+}
+
+impl<R> ErrorType for X25519Engine<R> {
+    type Error = ErrorKind;
+}
+
+impl<R> EcdhTypes for X25519Engine<R> {
+    type PrivateKey = StaticSecret;
+    type PublicKey = PublicKey;
+    type SharedSecret = [u8; 32];
+    type Curve = X25519;
+}
+
+impl<R: RngCore + CryptoRng> EcdhKeyGen for X25519Engine<R> {
+    fn generate_key_pair(
+        &mut self,
+        _curve: &Self::Curve,
+    ) -> Result<(Self::PrivateKey, Self::PublicKey), Self::Error> {
+        let private_key = StaticSecret::random_from_rng(&mut self.rng);
+        let public_key = PublicKey::from(&private_key);
+        Ok((private_key, public_key))
+    }
+}
+
+impl<R> EcdhValidate for X25519Engine<R> {
+    fn check_public_key(
+        &mut self,
+        _curve: &Self::Curve,
+        public_key: &Self::PublicKey,
+    ) -> Result<(), Self::Error> {
+        // Clamped scalars are multiples of the cofactor, so they map exactly
+        // the points of small order to the all-zero output.
+        let probe = StaticSecret::from([1; 32]);
+        if !probe.diffie_hellman(public_key).was_contributory() {
+            return Err(ErrorKind::InvalidPublicKey);
+        }
+        Ok(())
+    }
+}
+
+impl<R> Ecdh for X25519Engine<R> {
+    fn agree(
+        &mut self,
+        _curve: &Self::Curve,
+        private_key: &Self::PrivateKey,
+        peer_public_key: &Validated<Self::PublicKey>,
+    ) -> Result<Self::SharedSecret, Self::Error> {
+        let shared_secret = private_key.diffie_hellman(peer_public_key);
+        if !shared_secret.was_contributory() {
+            return Err(ErrorKind::InvalidPublicKey);
+        }
+        Ok(shared_secret.to_bytes())
+    }
+}
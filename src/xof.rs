@@ -0,0 +1,174 @@
+//! Extendable-output functions (XOFs).
+//!
+//! An XOF absorbs input like a digest, but can then be squeezed for an
+//! arbitrary amount of output. The error types are shared with the
+//! [`digest`](crate::digest) module.
+
+pub use crate::digest::{Error, ErrorKind, ErrorType};
+
+/// An extendable-output function.
+///
+/// Implemented by marker types so that the algorithm is known at compile time.
+pub trait XofAlgorithm {
+    /// Security strength of the function in bits.
+    const SECURITY_BITS: usize;
+}
+
+/// SHAKE128 (FIPS 202).
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Shake128;
+
+impl XofAlgorithm for Shake128 {
+    const SECURITY_BITS: usize = 128;
+}
+
+/// SHAKE256 (FIPS 202).
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Shake256;
+
+impl XofAlgorithm for Shake256 {
+    const SECURITY_BITS: usize = 256;
+}
+
+/// cSHAKE128 (NIST SP 800-185).
+///
+/// With an empty function name and customization string, cSHAKE128 is
+/// identical to SHAKE128.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CShake128<'a> {
+    /// Function-name string `N`, reserved for functions defined by NIST.
+    pub function_name: &'a [u8],
+    /// Customization string `S`.
+    pub customization: &'a [u8],
+}
+
+impl<'a> CShake128<'a> {
+    pub const fn new(function_name: &'a [u8], customization: &'a [u8]) -> Self {
+        Self {
+            function_name,
+            customization,
+        }
+    }
+}
+
+impl XofAlgorithm for CShake128<'_> {
+    const SECURITY_BITS: usize = 128;
+}
+
+/// cSHAKE256 (NIST SP 800-185).
+///
+/// With an empty function name and customization string, cSHAKE256 is
+/// identical to SHAKE256.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct CShake256<'a> {
+    /// Function-name string `N`, reserved for functions defined by NIST.
+    pub function_name: &'a [u8],
+    /// Customization string `S`.
+    pub customization: &'a [u8],
+}
+
+impl<'a> CShake256<'a> {
+    pub const fn new(function_name: &'a [u8], customization: &'a [u8]) -> Self {
+        Self {
+            function_name,
+            customization,
+        }
+    }
+}
+
+impl XofAlgorithm for CShake256<'_> {
+    const SECURITY_BITS: usize = 256;
+}
+
+/// An engine that can start XOF computations.
+///
+/// As with [`DigestInit`](crate::digest::DigestInit), the returned operation
+/// context borrows the engine exclusively until it is dropped.
+pub trait XofInit<A: XofAlgorithm>: ErrorType {
+    /// The operation context returned by [`XofInit::init`].
+    ///
+    /// Implementations should release any hardware resources held by the
+    /// operation when the context, or the reader it turns into, is dropped.
+    type OpContext<'a>: Xof + ErrorType<Error = Self::Error>
+    where
+        Self: 'a;
+
+    /// Init instance of the crypto function with the given context.
+    ///
+    /// # Parameters
+    ///
+    /// - `init_params`: The function to compute, including any customization.
+    ///
+    /// # Returns
+    ///
+    /// An operation context borrowing the engine until it is dropped.
+    fn init(&mut self, init_params: A) -> Result<Self::OpContext<'_>, Self::Error>;
+}
+
+/// An XOF computation in its absorbing phase.
+pub trait Xof: ErrorType {
+    /// The squeezing phase of the computation.
+    type Reader: XofReader + ErrorType<Error = Self::Error>;
+
+    /// Absorb input data.
+    ///
+    /// # Parameters
+    ///
+    /// - `input`: The input data to be absorbed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns a `CryptoError`.
+    fn absorb(&mut self, input: &[u8]) -> Result<(), Self::Error>;
+
+    /// Absorb several input slices, in order.
+    ///
+    /// The result is the same as calling [`absorb`](Self::absorb) on each slice
+    /// in turn. Implementations backed by a DMA-capable engine should override
+    /// this to process the slices as one scatter-gather list.
+    ///
+    /// # Parameters
+    ///
+    /// - `inputs`: The input slices to be absorbed.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns a `CryptoError`.
+    fn absorb_vectored(&mut self, inputs: &[&[u8]]) -> Result<(), Self::Error> {
+        for input in inputs {
+            self.absorb(input)?;
+        }
+        Ok(())
+    }
+
+    /// Reset instance to its initial state.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns a `CryptoError`.
+    fn reset(&mut self) -> Result<(), Self::Error>;
+
+    /// Finish absorbing and switch to squeezing.
+    ///
+    /// # Returns
+    ///
+    /// A reader from which the output can be squeezed. On failure, returns a `CryptoError`.
+    fn finalize(self) -> Result<Self::Reader, Self::Error>;
+}
+
+/// An XOF computation in its squeezing phase.
+pub trait XofReader: ErrorType {
+    /// Squeeze the next output bytes.
+    ///
+    /// Output is a continuous stream: squeezing `a` bytes followed by `b` bytes
+    /// yields the same data as squeezing `a + b` bytes at once.
+    ///
+    /// # Parameters
+    ///
+    /// - `out`: A mutable slice to fill with output.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` indicating success or failure. On success, returns `Ok(())`. On failure, returns a `CryptoError`.
+    fn squeeze(&mut self, out: &mut [u8]) -> Result<(), Self::Error>;
+}
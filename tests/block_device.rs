@@ -0,0 +1,176 @@
+//! Tests of [`hash_region`] against an in-memory block device.
+
+#![cfg(feature = "sw-sha2")]
+
+use peripheral_traits::block_device::{
+    hash_region, BlockDevice, Error, ErrorKind, ErrorType, HashRegionError,
+};
+use peripheral_traits::digest::{Digest, ErrorKind as DigestErrorKind};
+use peripheral_traits::sw::sha2::Sha256Context;
+
+#[derive(Debug, PartialEq)]
+struct MockError;
+
+impl Error for MockError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::ReadError
+    }
+}
+
+/// A device whose capacity need not be a multiple of its read size. Reads
+/// must start on a block and cover whole blocks, except that the last block
+/// of the device is short.
+struct MockDevice {
+    memory: Vec<u8>,
+    read_size: usize,
+    reads: usize,
+}
+
+impl MockDevice {
+    fn new(capacity: usize, read_size: usize) -> Self {
+        Self {
+            memory: (0..capacity).map(|i| (i * 31 + i / 256) as u8).collect(),
+            read_size,
+            reads: 0,
+        }
+    }
+}
+
+impl ErrorType for MockDevice {
+    type Error = MockError;
+}
+
+impl BlockDevice for MockDevice {
+    fn read_size(&self) -> usize {
+        self.read_size
+    }
+
+    fn read(&mut self, block_addr: usize, data: &mut [u8]) -> Result<(), MockError> {
+        let end = block_addr + data.len();
+        assert_eq!(block_addr % self.read_size, 0, "unaligned read address");
+        assert!(end <= self.memory.len(), "read past the end of the device");
+        assert!(
+            data.len().is_multiple_of(self.read_size) || end == self.memory.len(),
+            "partial block read"
+        );
+        data.copy_from_slice(&self.memory[block_addr..end]);
+        self.reads += 1;
+        Ok(())
+    }
+
+    fn erase_size(&self) -> usize {
+        self.read_size
+    }
+
+    fn erase(&mut self, _block_addr: usize, _size_in_bytes: usize) -> Result<(), MockError> {
+        Err(MockError)
+    }
+
+    fn program_size(&self) -> usize {
+        self.read_size
+    }
+
+    fn program(&mut self, _block_addr: usize, _data: &[u8]) -> Result<(), MockError> {
+        Err(MockError)
+    }
+
+    fn capacity(&self) -> usize {
+        self.memory.len()
+    }
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut digest = Sha256Context::new();
+    digest.update(data).unwrap();
+    digest.finalize().unwrap().into_array()
+}
+
+fn hash_device(
+    device: &mut MockDevice,
+    address: usize,
+    length: usize,
+    scratch: &mut [u8],
+) -> Result<[u8; 32], HashRegionError<MockError, DigestErrorKind>> {
+    let mut digest = Sha256Context::new();
+    hash_region(device, &mut digest, address, length, scratch)?;
+    Ok(digest.finalize().unwrap().into_array())
+}
+
+#[test]
+fn hashes_unaligned_regions() {
+    // 1000 is not a multiple of 16, so the last block is 8 bytes long.
+    let mut device = MockDevice::new(1000, 16);
+    for scratch_size in [16, 17, 48, 100, 2048] {
+        let mut scratch = vec![0; scratch_size];
+        for (address, length) in [
+            (0, 1000),
+            (0, 0),
+            (5, 0),
+            (3, 1),
+            (15, 2),
+            (17, 300),
+            (32, 64),
+            (990, 10),
+            (992, 8),
+            (999, 1),
+            (1000, 0),
+            (1, 999),
+        ] {
+            let expected = sha256(&device.memory[address..address + length]);
+            assert_eq!(
+                hash_device(&mut device, address, length, &mut scratch),
+                Ok(expected),
+                "address {address}, length {length}, scratch {scratch_size}"
+            );
+        }
+    }
+}
+
+#[test]
+fn scratch_larger_than_the_region_needs_one_read() {
+    let mut device = MockDevice::new(1000, 16);
+    let mut scratch = [0; 512];
+    let expected = sha256(&device.memory[900..1000]);
+    assert_eq!(
+        hash_device(&mut device, 900, 100, &mut scratch),
+        Ok(expected)
+    );
+    assert_eq!(device.reads, 1);
+}
+
+#[test]
+fn scratch_smaller_than_the_region_is_reused() {
+    let mut device = MockDevice::new(1000, 16);
+    let mut scratch = [0; 40];
+    let expected = sha256(&device.memory[10..330]);
+    assert_eq!(
+        hash_device(&mut device, 10, 320, &mut scratch),
+        Ok(expected)
+    );
+    // 0..336 in reads of 32 bytes.
+    assert_eq!(device.reads, 11);
+}
+
+#[test]
+fn rejects_scratch_smaller_than_a_block() {
+    let mut device = MockDevice::new(1000, 16);
+    let mut scratch = [0; 15];
+    assert_eq!(
+        hash_device(&mut device, 0, 10, &mut scratch),
+        Err(HashRegionError::ScratchTooSmall)
+    );
+    assert_eq!(device.reads, 0);
+}
+
+#[test]
+fn rejects_regions_past_the_end() {
+    let mut device = MockDevice::new(1000, 16);
+    let mut scratch = [0; 64];
+    for (address, length) in [(995, 6), (1000, 1), (1001, 0), (usize::MAX, 2)] {
+        assert_eq!(
+            hash_device(&mut device, address, length, &mut scratch),
+            Err(HashRegionError::OutOfBounds)
+        );
+    }
+    assert_eq!(device.reads, 0);
+}
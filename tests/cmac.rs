@@ -0,0 +1,127 @@
+//! SP 800-38B and FIPS 197 vectors for the software AES-CMAC backend.
+
+#![cfg(feature = "sw-aes-cmac")]
+
+mod common;
+
+use common::hex;
+use peripheral_traits::key::KeyHandle;
+use peripheral_traits::mac::{AesCmac, ErrorKind, Mac, MacInit};
+use peripheral_traits::sw::cmac::AesCmacEngine;
+
+fn cmac(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut engine = AesCmacEngine;
+    let mut cmac = engine.init(AesCmac).unwrap();
+    cmac.set_key(KeyHandle::Raw(key)).unwrap();
+    cmac.update(message).unwrap();
+    let mut tag = [0; 16];
+    cmac.finalize(&mut tag).unwrap();
+
+    // The same message fed in pieces that straddle block boundaries.
+    for piece in message.chunks(7) {
+        cmac.update(piece).unwrap();
+    }
+    let mut again = [0; 16];
+    cmac.finalize(&mut again).unwrap();
+    assert_eq!(again, tag);
+    tag.to_vec()
+}
+
+const KEY_128: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+const KEY_192: &str = "8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b";
+const KEY_256: &str = "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+const MESSAGE: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                       30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+/// Checks the SP 800-38B examples of one key size, over the first 0, 16, 40
+/// and 64 bytes of [`MESSAGE`].
+fn check_examples(key: &str, tags: [&str; 4]) {
+    let key = hex(key);
+    let message = hex(MESSAGE);
+    for (len, tag) in [0, 16, 40, 64].into_iter().zip(tags) {
+        assert_eq!(cmac(&key, &message[..len]), hex(tag), "{len} bytes");
+    }
+}
+
+/// The AES-128 examples are also RFC 4493 examples 1 to 4.
+#[test]
+fn aes128_examples() {
+    check_examples(
+        KEY_128,
+        [
+            "bb1d6929e95937287fa37d129b756746",
+            "070a16b46b4d4144f79bdd9dd04a287c",
+            "dfa66747de9ae63030ca32611497c827",
+            "51f0bebf7e3b9d92fc49741779363cfe",
+        ],
+    );
+}
+
+#[test]
+fn aes192_examples() {
+    check_examples(
+        KEY_192,
+        [
+            "d17ddf46adaacde531cac483de7a9367",
+            "9e99a7bf31e710900662f65e617c5184",
+            "8a1de5be2eb31aad089a82e6ee908b0e",
+            "a1d5df0eed790f794d77589659f39a11",
+        ],
+    );
+}
+
+#[test]
+fn aes256_examples() {
+    check_examples(
+        KEY_256,
+        [
+            "028962f61b7bf89efc6b551f4667d983",
+            "28a7023f452e8f82bd4bf28d8c37c35c",
+            "aaf3d8f1de5640c232f5b169b9c911e6",
+            "e1992190549f6ed5696a2c056c315410",
+        ],
+    );
+}
+
+/// FIPS 197 appendix C, through CMAC since the block cipher is not public.
+///
+/// The tag of a one-block message `M` is `AES(K, M ^ K1)`, so the message
+/// `P ^ K1` has the ciphertext of `P` as its tag. `K1` is the first CMAC
+/// subkey of the key, `dbl(AES(K, 0^128))`.
+#[test]
+fn fips_197_appendix_c() {
+    let plaintext = hex("00112233445566778899aabbccddeeff");
+    for (key_len, k1, ciphertext) in [
+        (
+            16,
+            "8d42766f0f1eb704de9f02c54391b075",
+            "69c4e0d86a7b0430d8cdb78070b4c55a",
+        ),
+        (
+            24,
+            "22c4a30438e74a45872dac4e70032c89",
+            "dda97ca4864cdfe06eaf70a0ec0d7191",
+        ),
+        (
+            32,
+            "e520016c54933fa153e734d5ba5cef87",
+            "8ea2b7ca516745bfeafc49904b496089",
+        ),
+    ] {
+        let key: Vec<u8> = (0..key_len).collect();
+        let message: Vec<u8> = plaintext.iter().zip(hex(k1)).map(|(p, k)| p ^ k).collect();
+        assert_eq!(cmac(&key, &message), hex(ciphertext), "{key_len}-byte key");
+    }
+}
+
+#[test]
+fn rejects_invalid_keys() {
+    let mut engine = AesCmacEngine;
+    let mut cmac = engine.init(AesCmac).unwrap();
+    for len in [0, 15, 17, 31, 33] {
+        assert_eq!(
+            cmac.set_key(KeyHandle::Raw(&vec![0; len])),
+            Err(ErrorKind::InvalidKeySize)
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+/// Decodes a hex string.
+pub fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Deterministic generator standing in for a TRNG.
+#[cfg(any(feature = "sw-x25519", feature = "sw-ed25519", feature = "sw-ecdsa"))]
+pub struct CountingRng(pub u8);
+
+#[cfg(any(feature = "sw-x25519", feature = "sw-ed25519", feature = "sw-ecdsa"))]
+impl rand_core::RngCore for CountingRng {
+    fn next_u32(&mut self) -> u32 {
+        rand_core::impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest {
+            self.0 = self.0.wrapping_add(1);
+            *byte = self.0;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(any(feature = "sw-x25519", feature = "sw-ed25519", feature = "sw-ecdsa"))]
+impl rand_core::CryptoRng for CountingRng {}
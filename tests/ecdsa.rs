@@ -0,0 +1,351 @@
+//! RFC 6979 appendix A.2.5 and A.2.6 signatures, with the software ECDSA backend.
+
+#![cfg(feature = "sw-ecdsa")]
+
+mod common;
+
+use common::{hex, CountingRng};
+use p256::ecdsa::VerifyingKey;
+use peripheral_traits::digest::{Digest, DigestAlgorithm, Output, Sha2_256, Sha3_256};
+use peripheral_traits::ecdsa::encoding::{Error, Sec1Encoding};
+use peripheral_traits::ecdsa::message::{sign_message, verify_message};
+use peripheral_traits::ecdsa::{
+    EcdsaKeyGen, EcdsaSign, EcdsaSignWithNonce, EcdsaValidate, EcdsaVerify, Error as _, ErrorKind,
+    NonceMode, P256, P384,
+};
+use peripheral_traits::sw::ecdsa::EcdsaEngine;
+use peripheral_traits::sw::sha2::{Sha256Context, Sha384Context, Sha512Context};
+use peripheral_traits::sw::sha3::Sha3_256Context;
+
+const P256_KEY: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+const P256_PUBLIC: &str = "0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6\
+                           7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299";
+const P384_KEY: &str = "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d8\
+                        96d5724e4c70a825f872c9ea60d2edf5";
+const P384_PUBLIC: &str = "04ec3a4e415b4e19a4568618029f427fa5da9a8bc4ae92e02e06aae5286b300c64\
+                           def8f0ea9055866064a254515480bc138015d9b72d7d57244ea8ef9ac0c621896708\
+                           a59367f9dfb9f54ca84b3f1c9db1288b231c3ae0d4fe7344fd2533264720";
+
+fn p256_engine() -> EcdsaEngine<P256, CountingRng> {
+    EcdsaEngine::new(CountingRng(0))
+}
+
+fn p256_keys() -> (p256::ecdsa::SigningKey, p256::ecdsa::VerifyingKey) {
+    let private_key = p256::ecdsa::SigningKey::from_slice(&hex(P256_KEY)).unwrap();
+    let public_key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&hex(P256_PUBLIC)).unwrap();
+    assert_eq!(*private_key.verifying_key(), public_key);
+    (private_key, public_key)
+}
+
+#[test]
+fn p256_sha256() {
+    let mut engine = p256_engine();
+    let (private_key, public_key) = p256_keys();
+    let public_key = engine.validate(&P256, public_key).unwrap();
+
+    for (message, signature) in [
+        (
+            "sample",
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716\
+             f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+        ),
+        (
+            "test",
+            "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367\
+             019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+        ),
+    ] {
+        let mut digest = Sha256Context::new();
+        let sig = sign_message(&mut engine, &P256, &private_key, &mut digest, message).unwrap();
+        assert_eq!(sig.to_bytes()[..], hex(signature));
+        verify_message(&mut engine, &P256, &public_key, &mut digest, message, &sig).unwrap();
+    }
+}
+
+#[test]
+fn p256_sha512() {
+    let mut engine = p256_engine();
+    let (private_key, _) = p256_keys();
+    let sig = sign_message(
+        &mut engine,
+        &P256,
+        &private_key,
+        &mut Sha512Context::new(),
+        "sample",
+    )
+    .unwrap();
+    assert_eq!(
+        sig.to_bytes()[..],
+        hex(
+            "8496a60b5e9b47c825488827e0495b0e3fa109ec4568fd3f8d1097678eb97f00\
+             2362ab1adbe2b8adf9cb9edab740ea6049c028114f2460f96554f61fae3302fe"
+        )
+    );
+}
+
+/// RFC 6979 has no SHA-3 vectors; these were generated with OpenSSL 3.5
+/// deterministic signing, which uses HMAC with the message's hash.
+#[test]
+fn p256_sha3_256() {
+    let mut engine = p256_engine();
+    let (private_key, public_key) = p256_keys();
+    let public_key = engine.validate(&P256, public_key).unwrap();
+
+    for (message, signature) in [
+        (
+            "sample",
+            "8fedfdf147364db550f840aebfe7c26df77a9ab56c9aea20ac33e45e1aedd7ac\
+             3a5bd6183374df2517910db14e0a9cc4666ae679c4d1ebb89242fb3062db6068",
+        ),
+        (
+            "test",
+            "daceab516e4d584453d8ee7cba66b1ff17cd9d6f4c228d2f0a9bed361d03fd01\
+             dbdaae4d5625eb2f087e87cb537614aaff928062797fc0288e1ac76436831c41",
+        ),
+    ] {
+        let mut digest = Sha3_256Context::new();
+        digest.update(message.as_bytes()).unwrap();
+        let message_hash = digest.finalize().unwrap();
+        let sig = engine
+            .sign_with_nonce::<Sha3_256>(
+                &P256,
+                &private_key,
+                message_hash,
+                NonceMode::Deterministic,
+            )
+            .unwrap();
+        assert_eq!(sig.to_bytes()[..], hex(signature));
+        engine
+            .verify::<Sha3_256>(&P256, &public_key, message_hash, &sig)
+            .unwrap();
+    }
+}
+
+/// SHA-512/256, which the software backend has no HMAC for.
+#[derive(Debug, Default, Copy, Clone)]
+struct Sha2_512_256;
+
+impl DigestAlgorithm for Sha2_512_256 {
+    const OUTPUT_BITS: usize = 256;
+    const BLOCK_SIZE: usize = 128;
+    const OID: &'static [u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x06];
+    type DigestOutput = Output<32>;
+}
+
+#[test]
+fn unknown_hashes_only_sign_with_random_nonces() {
+    let mut engine = p256_engine();
+    let (private_key, public_key) = p256_keys();
+    let public_key = engine.validate(&P256, public_key).unwrap();
+    let message_hash = [0x5a; 32];
+
+    for nonce in [
+        NonceMode::Deterministic,
+        NonceMode::Hedged {
+            extra_entropy: &[1; 32],
+        },
+    ] {
+        assert_eq!(
+            engine.sign_with_nonce::<Sha2_512_256>(&P256, &private_key, message_hash, nonce),
+            Err(ErrorKind::UnsupportedNonceMode)
+        );
+    }
+    let sig = engine
+        .sign_with_nonce::<Sha2_512_256>(&P256, &private_key, message_hash, NonceMode::Random)
+        .unwrap();
+    engine
+        .verify::<Sha2_512_256>(&P256, &public_key, message_hash, &sig)
+        .unwrap();
+}
+
+#[test]
+fn p384_sha384() {
+    let mut engine = EcdsaEngine::<P384, _>::new(CountingRng(0));
+    let private_key = p384::ecdsa::SigningKey::from_slice(&hex(P384_KEY)).unwrap();
+    let public_key = p384::ecdsa::VerifyingKey::from_sec1_bytes(&hex(P384_PUBLIC)).unwrap();
+    let public_key = engine.validate(&P384, public_key).unwrap();
+
+    for (message, signature) in [
+        (
+            "sample",
+            "94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa73d64c4ea95ad133c\
+             81a648152e44acf96e36dd1e80fabe4699ef4aeb15f178cea1fe40db2603138f\
+             130e740a19624526203b6351d0a3a94fa329c145786e679e7b82c71a38628ac8",
+        ),
+        (
+            "test",
+            "8203b63d3c853e8d77227fb377bcf7b7b772e97892a80f36ab775d509d7a5feb\
+             0542a7f0812998da8f1dd3ca3cf023dbddd0760448d42d8a43af45af836fce4d\
+             e8be06b485e9b61b827c2f13173923e06a739f040649a667bf3b828246baa5a5",
+        ),
+    ] {
+        let mut digest = Sha384Context::new();
+        let sig = sign_message(&mut engine, &P384, &private_key, &mut digest, message).unwrap();
+        assert_eq!(sig.to_bytes()[..], hex(signature));
+        verify_message(&mut engine, &P384, &public_key, &mut digest, message, &sig).unwrap();
+    }
+}
+
+#[test]
+fn nonce_modes() {
+    let mut engine = p256_engine();
+    let (private_key, public_key) = p256_keys();
+    let public_key = engine.validate(&P256, public_key).unwrap();
+    let message_hash = [0x5a; 32];
+
+    let deterministic = engine
+        .sign_with_nonce::<Sha2_256>(&P256, &private_key, message_hash, NonceMode::Deterministic)
+        .unwrap();
+    assert_eq!(
+        engine
+            .sign::<Sha2_256>(&P256, &private_key, message_hash)
+            .unwrap(),
+        deterministic
+    );
+
+    let hedged = NonceMode::Hedged {
+        extra_entropy: &[1; 32],
+    };
+    let first = engine
+        .sign_with_nonce::<Sha2_256>(&P256, &private_key, message_hash, hedged)
+        .unwrap();
+    let random = engine
+        .sign_with_nonce::<Sha2_256>(&P256, &private_key, message_hash, NonceMode::Random)
+        .unwrap();
+    assert_ne!(first, deterministic);
+    assert_ne!(random, deterministic);
+
+    for sig in [deterministic, first, random] {
+        engine
+            .verify::<Sha2_256>(&P256, &public_key, message_hash, &sig)
+            .unwrap();
+    }
+}
+
+#[test]
+fn rejects_modified_message_and_wrong_hash_size() {
+    let mut engine = p256_engine();
+    let (private_key, public_key) = p256_keys();
+    let public_key = engine.validate(&P256, public_key).unwrap();
+
+    let sig = engine
+        .sign::<Sha2_256>(&P256, &private_key, [0x5a; 32])
+        .unwrap();
+    assert_eq!(
+        engine.verify::<Sha2_256>(&P256, &public_key, [0x5b; 32], &sig),
+        Err(ErrorKind::InvalidSignature)
+    );
+    assert_eq!(
+        engine.sign::<Sha2_256>(&P256, &private_key, [0x5a; 48]),
+        Err(ErrorKind::SigningError)
+    );
+}
+
+#[test]
+fn verify_rejects_wrong_hash_size() {
+    let mut engine = p256_engine();
+    let (private_key, public_key) = p256_keys();
+    let public_key = engine.validate(&P256, public_key).unwrap();
+
+    // The curve would truncate a 48-byte hash to its first 32 bytes, so the
+    // signature is valid for it but the hash is not a SHA-256 one.
+    let sig = engine
+        .sign::<Sha2_256>(&P256, &private_key, [0x5a; 32])
+        .unwrap();
+    for len in [0, 31, 33, 48] {
+        assert_eq!(
+            engine.verify::<Sha2_256>(&P256, &public_key, vec![0x5a; len], &sig),
+            Err(ErrorKind::InvalidSignature),
+            "{len} bytes"
+        );
+    }
+}
+
+/// Decodes `point` and validates the key, as a caller receiving it would.
+fn decode_and_validate(point: &[u8]) -> Result<VerifyingKey, ErrorKind> {
+    let public_key = VerifyingKey::from_sec1(point).map_err(|e| e.kind())?;
+    let public_key = p256_engine().validate(&P256, public_key)?;
+    Ok(*public_key)
+}
+
+#[test]
+fn invalid_points_never_become_validated() {
+    let public = hex(P256_PUBLIC);
+    let mut off_curve = public.clone();
+    off_curve[64] ^= 1;
+    let mut hybrid = public.clone();
+    hybrid[0] = 0x07;
+    let mut x_above_p = public.clone();
+    x_above_p[1..33].fill(0xff);
+    // An x-coordinate with no point above it: x^3 - 3x + b is not a square.
+    let mut no_y = vec![0x02];
+    no_y.extend_from_slice(&[0; 31]);
+    no_y.push(0x02);
+
+    for (point, error) in [
+        (&[0][..], Error::Identity),
+        (&off_curve, Error::NotOnCurve),
+        (&no_y, Error::NotOnCurve),
+        (&hybrid, Error::InvalidFormat),
+        (&x_above_p, Error::OutOfRange),
+        (&public[..33], Error::InvalidLength),
+    ] {
+        assert_eq!(VerifyingKey::from_sec1(point), Err(error), "{point:02x?}");
+        assert_eq!(
+            decode_and_validate(point),
+            Err(ErrorKind::InvalidEncoding),
+            "{point:02x?}"
+        );
+    }
+
+    let (_, expected) = p256_keys();
+    assert_eq!(decode_and_validate(&public), Ok(expected));
+}
+
+#[test]
+fn public_keys_round_trip_through_sec1() {
+    let (_, public_key) = p256_keys();
+    let public = hex(P256_PUBLIC);
+
+    let mut out = [0; 65];
+    assert_eq!(public_key.to_sec1(false, &mut out), Ok(65));
+    assert_eq!(out[..], public);
+    assert_eq!(public_key.to_sec1(true, &mut out), Ok(33));
+    assert_eq!(out[0], 0x03);
+    assert_eq!(out[1..33], public[1..33]);
+    assert_eq!(VerifyingKey::from_sec1(&out[..33]), Ok(public_key));
+    assert_eq!(
+        public_key.to_sec1(false, &mut [0; 64]),
+        Err(Error::BufferTooSmall)
+    );
+
+    let mut engine = EcdsaEngine::<P384, _>::new(CountingRng(0));
+    let (_, public_key) = engine.generate_key_pair(&P384).unwrap();
+    let mut out = [0; 97];
+    let len = public_key.to_sec1(false, &mut out).unwrap();
+    assert_eq!(
+        p384::ecdsa::VerifyingKey::from_sec1(&out[..len]),
+        Ok(public_key)
+    );
+}
+
+#[test]
+fn generated_keys_round_trip() {
+    let mut engine = EcdsaEngine::<P384, _>::new(CountingRng(0));
+    let (private_key, public_key) = engine.generate_key_pair(&P384).unwrap();
+    let (other_private_key, _) = engine.generate_key_pair(&P384).unwrap();
+    assert_ne!(private_key, other_private_key);
+
+    let public_key = engine.validate(&P384, public_key).unwrap();
+    let mut digest = Sha384Context::new();
+    let sig = sign_message(&mut engine, &P384, &private_key, &mut digest, b"message").unwrap();
+    verify_message(
+        &mut engine,
+        &P384,
+        &public_key,
+        &mut digest,
+        b"message",
+        &sig,
+    )
+    .unwrap();
+}
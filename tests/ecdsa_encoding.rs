@@ -0,0 +1,285 @@
+//! Tests of the strict SEC1 and DER parsers of the ECDSA encodings.
+
+mod common;
+
+use common::hex;
+use peripheral_traits::ecdsa::encoding::{
+    der_to_raw, max_der_signature_size, raw_signature_size, raw_to_der, Error, Sec1Point,
+};
+use peripheral_traits::ecdsa::{EcdsaCurve, P256, P521};
+
+/// RFC 6979 appendix A.2.5 public key and signature over "sample" with SHA-256.
+const P256_X: &str = "60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6";
+const P256_Y: &str = "7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299";
+const R: &str = "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716";
+const S: &str = "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8";
+
+fn concat(parts: &[&[u8]]) -> Vec<u8> {
+    parts.concat()
+}
+
+/// A DER INTEGER holding `value` as given, without normalizing it.
+fn integer(value: &[u8]) -> Vec<u8> {
+    concat(&[&[0x02, value.len() as u8], value])
+}
+
+/// A DER SEQUENCE with a short-form length.
+fn sequence(content: &[u8]) -> Vec<u8> {
+    concat(&[&[0x30, content.len() as u8], content])
+}
+
+fn p256_der(r: &[u8], s: &[u8]) -> Vec<u8> {
+    sequence(&concat(&[&integer(r), &integer(s)]))
+}
+
+fn p256_raw(der: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut raw = [0; 64];
+    let len = der_to_raw::<P256>(der, &mut raw)?;
+    Ok(raw[..len].to_vec())
+}
+
+fn p256_to_der(raw: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut der = [0; max_der_signature_size::<P256>()];
+    let len = raw_to_der::<P256>(raw, &mut der)?;
+    Ok(der[..len].to_vec())
+}
+
+/// `value + 1` as a big-endian integer of the same length.
+fn plus_one(value: &[u8]) -> Vec<u8> {
+    let mut value = value.to_vec();
+    for byte in value.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+    value
+}
+
+#[test]
+fn p256_signature_round_trip() {
+    let raw = concat(&[&hex(R), &hex(S)]);
+    // Both components have the top bit set and need a leading zero.
+    let der = p256_der(&concat(&[&[0], &hex(R)]), &concat(&[&[0], &hex(S)]));
+    assert_eq!(der.len(), 72);
+    assert_eq!(p256_to_der(&raw), Ok(der.clone()));
+    assert_eq!(p256_raw(&der), Ok(raw));
+
+    // Small components are written in as few bytes as possible.
+    let mut raw = [0; 64];
+    raw[31] = 1;
+    raw[63] = 0x80;
+    let der = p256_der(&[1], &[0, 0x80]);
+    assert_eq!(p256_to_der(&raw), Ok(der.clone()));
+    assert_eq!(p256_raw(&der), Ok(raw.to_vec()));
+}
+
+/// P-521 signatures need the long form `0x81 len` for the SEQUENCE length.
+#[test]
+fn p521_signature_round_trip() {
+    // n - 1 and 2^520, both 66 bytes long without a leading zero.
+    let mut r = P521::ORDER.to_vec();
+    r[65] -= 1;
+    let mut s = [0; 66];
+    s[0] = 1;
+    let raw = concat(&[&r, &s]);
+
+    let content = concat(&[&integer(&r), &integer(&s)]);
+    assert_eq!(content.len(), 0x88);
+    let der = concat(&[&[0x30, 0x81, 0x88], &content]);
+    assert_eq!(der.len(), max_der_signature_size::<P521>() - 2);
+
+    let mut out = [0; max_der_signature_size::<P521>()];
+    let len = raw_to_der::<P521>(&raw, &mut out).unwrap();
+    assert_eq!(out[..len], der);
+    let mut out = [0; raw_signature_size::<P521>()];
+    assert_eq!(der_to_raw::<P521>(&der, &mut out), Ok(out.len()));
+    assert_eq!(out[..], raw);
+
+    // Without the 0x81, the length byte 0x88 announces eight length bytes.
+    let mut short = der.clone();
+    short.remove(1);
+    assert_eq!(
+        der_to_raw::<P521>(&short, &mut out),
+        Err(Error::InvalidLength)
+    );
+}
+
+#[test]
+fn der_rejects_non_minimal_lengths_and_integers() {
+    let r = concat(&[&[0], &hex(R)]);
+    let s = concat(&[&[0], &hex(S)]);
+
+    // 0x81 followed by a length that fits the short form.
+    let content = concat(&[&integer(&r), &integer(&s)]);
+    let long_form = concat(&[&[0x30, 0x81, content.len() as u8], &content]);
+    assert_eq!(p256_raw(&long_form), Err(Error::NonCanonical));
+    let long_integer = sequence(&concat(&[&[0x02, 0x81, 0x21], &r, &integer(&s)]));
+    assert_eq!(p256_raw(&long_integer), Err(Error::NonCanonical));
+    // Lengths of more than one byte are never needed.
+    let two_bytes = concat(&[&[0x30, 0x82, 0x00, content.len() as u8], &content]);
+    assert_eq!(p256_raw(&two_bytes), Err(Error::InvalidLength));
+
+    // A leading zero not followed by a byte with the top bit set.
+    assert_eq!(p256_raw(&p256_der(&[0, 1], &s)), Err(Error::NonCanonical));
+    assert_eq!(
+        p256_raw(&p256_der(&r, &[0, 0x7f])),
+        Err(Error::NonCanonical)
+    );
+    assert_eq!(
+        p256_raw(&p256_der(&concat(&[&[0], &r]), &s)),
+        Err(Error::NonCanonical)
+    );
+    // An empty INTEGER.
+    assert_eq!(p256_raw(&p256_der(&[], &s)), Err(Error::InvalidLength));
+}
+
+#[test]
+fn der_rejects_out_of_range_components() {
+    let r = concat(&[&[0], &hex(R)]);
+    let s = concat(&[&[0], &hex(S)]);
+    let n = concat(&[&[0], P256::ORDER]);
+    let n_plus_one = concat(&[&[0], &plus_one(P256::ORDER)]);
+
+    for (r, s) in [
+        // Negative.
+        (&hex(R)[..], &s[..]),
+        (&r[..], &hex(S)[..]),
+        (&[0xff][..], &s[..]),
+        // Zero.
+        (&[0][..], &s[..]),
+        (&r[..], &[0][..]),
+        // n and above.
+        (&n[..], &s[..]),
+        (&r[..], &n[..]),
+        (&n_plus_one[..], &s[..]),
+        // Longer than a scalar.
+        (&[1; 33][..], &s[..]),
+    ] {
+        assert_eq!(p256_raw(&p256_der(r, s)), Err(Error::OutOfRange));
+    }
+
+    let mut raw = concat(&[&hex(R), &hex(S)]);
+    raw[..32].fill(0);
+    assert_eq!(p256_to_der(&raw), Err(Error::OutOfRange));
+    raw[..32].copy_from_slice(P256::ORDER);
+    assert_eq!(p256_to_der(&raw), Err(Error::OutOfRange));
+    raw[..32].copy_from_slice(&hex(R));
+    raw[32..].copy_from_slice(&plus_one(P256::ORDER));
+    assert_eq!(p256_to_der(&raw), Err(Error::OutOfRange));
+}
+
+#[test]
+fn der_rejects_malformed_structure() {
+    let r = concat(&[&[0], &hex(R)]);
+    let s = concat(&[&[0], &hex(S)]);
+    let der = p256_der(&r, &s);
+
+    // Bytes after the SEQUENCE, or inside it after s.
+    assert_eq!(p256_raw(&concat(&[&der, &[0]])), Err(Error::InvalidLength));
+    let extra = sequence(&concat(&[&integer(&r), &integer(&s), &[0x05, 0x00]]));
+    assert_eq!(p256_raw(&extra), Err(Error::InvalidLength));
+    // Truncated, or missing s.
+    assert_eq!(p256_raw(&der[..der.len() - 1]), Err(Error::InvalidLength));
+    assert_eq!(p256_raw(&sequence(&integer(&r))), Err(Error::InvalidLength));
+    assert_eq!(p256_raw(&[]), Err(Error::InvalidLength));
+    // Wrong tags.
+    let mut wrong = der.clone();
+    wrong[0] = 0x31;
+    assert_eq!(p256_raw(&wrong), Err(Error::InvalidFormat));
+    let mut wrong = der.clone();
+    wrong[2] = 0x03;
+    assert_eq!(p256_raw(&wrong), Err(Error::InvalidFormat));
+
+    let mut raw = [0; 63];
+    assert_eq!(
+        der_to_raw::<P256>(&der, &mut raw),
+        Err(Error::BufferTooSmall)
+    );
+}
+
+#[test]
+fn raw_signature_must_have_exact_length() {
+    let raw = concat(&[&hex(R), &hex(S)]);
+    assert_eq!(p256_to_der(&raw[..63]), Err(Error::InvalidLength));
+    assert_eq!(
+        p256_to_der(&concat(&[&raw, &[1]])),
+        Err(Error::InvalidLength)
+    );
+    assert_eq!(p256_to_der(&[]), Err(Error::InvalidLength));
+
+    let mut der = [0; 71];
+    assert_eq!(
+        raw_to_der::<P256>(&raw, &mut der),
+        Err(Error::BufferTooSmall)
+    );
+}
+
+#[test]
+fn sec1_parses_both_forms() {
+    let (x, y) = (hex(P256_X), hex(P256_Y));
+    let uncompressed = concat(&[&[0x04], &x, &y]);
+    let point = Sec1Point::parse::<P256>(&uncompressed).unwrap();
+    assert_eq!(point, Sec1Point::Uncompressed { x: &x, y: &y });
+    let mut out = [0; 65];
+    assert_eq!(point.encode::<P256>(&mut out), Ok(65));
+    assert_eq!(out[..], uncompressed);
+
+    // y is odd.
+    let compressed = concat(&[&[0x03], &x]);
+    let point = Sec1Point::parse::<P256>(&compressed).unwrap();
+    assert_eq!(
+        point,
+        Sec1Point::Compressed {
+            x: &x,
+            y_is_odd: true
+        }
+    );
+    assert_eq!(point.x(), x);
+    assert_eq!(point.encode::<P256>(&mut out), Ok(33));
+    assert_eq!(out[..33], compressed);
+    assert_eq!(
+        point.encode::<P256>(&mut out[..32]),
+        Err(Error::BufferTooSmall)
+    );
+}
+
+#[test]
+fn sec1_rejects_invalid_points() {
+    let (x, y) = (hex(P256_X), hex(P256_Y));
+    let p = P256::PRIME;
+
+    // Hybrid points and unknown prefixes.
+    for prefix in [0x06, 0x07, 0x05, 0x01, 0xff] {
+        assert_eq!(
+            Sec1Point::parse::<P256>(&concat(&[&[prefix], &x, &y])),
+            Err(Error::InvalidFormat)
+        );
+    }
+    assert_eq!(Sec1Point::parse::<P256>(&[0]), Err(Error::Identity));
+
+    // Coordinates equal to or above p.
+    for point in [
+        concat(&[&[0x04], p, &y]),
+        concat(&[&[0x04], &x, p]),
+        concat(&[&[0x04], &[0xff; 32], &y]),
+        concat(&[&[0x04], &x, &plus_one(p)]),
+        concat(&[&[0x02], p]),
+        concat(&[&[0x03], &plus_one(p)]),
+    ] {
+        assert_eq!(Sec1Point::parse::<P256>(&point), Err(Error::OutOfRange));
+    }
+    let point = Sec1Point::Uncompressed { x: &x, y: p };
+    assert_eq!(point.encode::<P256>(&mut [0; 65]), Err(Error::OutOfRange));
+
+    // Lengths that do not match the prefix.
+    for point in [
+        concat(&[&[0x04], &x]),
+        concat(&[&[0x04], &x, &y, &[0]]),
+        concat(&[&[0x02], &x, &y]),
+        concat(&[&[0x03], &x[..31]]),
+        vec![],
+    ] {
+        assert_eq!(Sec1Point::parse::<P256>(&point), Err(Error::InvalidLength));
+    }
+}
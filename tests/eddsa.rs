@@ -0,0 +1,152 @@
+//! RFC 8032 section 7.1 and 7.3 test vectors, with the software Ed25519 backend.
+
+#![cfg(feature = "sw-ed25519")]
+
+mod common;
+
+use common::{hex, CountingRng};
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use peripheral_traits::eddsa::{
+    Ed25519, EddsaKeyGen, EddsaSign, EddsaVerify, ErrorKind, MAX_CONTEXT_SIZE,
+};
+use peripheral_traits::sw::ed25519::Ed25519Engine;
+
+fn engine() -> Ed25519Engine<CountingRng> {
+    Ed25519Engine::new(CountingRng(0))
+}
+
+struct Vector {
+    secret: &'static str,
+    public: &'static str,
+    message: &'static str,
+    signature: &'static str,
+}
+
+fn keys(vector: &Vector) -> (SigningKey, VerifyingKey) {
+    let private_key = SigningKey::from_bytes(&hex(vector.secret).try_into().unwrap());
+    let public_key = VerifyingKey::from_bytes(&hex(vector.public).try_into().unwrap()).unwrap();
+    assert_eq!(private_key.verifying_key(), public_key);
+    (private_key, public_key)
+}
+
+fn signature(vector: &Vector) -> Signature {
+    Signature::from_slice(&hex(vector.signature)).unwrap()
+}
+
+const ED25519: [Vector; 3] = [
+    // TEST 1
+    Vector {
+        secret: "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+        public: "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+        message: "",
+        signature: "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+    },
+    // TEST 2
+    Vector {
+        secret: "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+        public: "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+        message: "72",
+        signature: "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+    },
+    // TEST 3
+    Vector {
+        secret: "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+        public: "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+        message: "af82",
+        signature: "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+    },
+];
+
+// TEST abc. The message is "abc" and its prehash is SHA-512("abc").
+const ED25519PH: Vector = Vector {
+    secret: "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42",
+    public: "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf",
+    message: "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+    signature: "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406",
+};
+
+#[test]
+fn ed25519_sign_and_verify() {
+    let mut engine = engine();
+    for vector in &ED25519 {
+        let (private_key, public_key) = keys(vector);
+        let message = hex(vector.message);
+
+        let sig = engine.sign(&Ed25519, &private_key, &message, &[]).unwrap();
+        assert_eq!(sig.to_bytes()[..], hex(vector.signature));
+        engine
+            .verify(&Ed25519, &public_key, &message, &[], &signature(vector))
+            .unwrap();
+    }
+}
+
+#[test]
+fn ed25519_rejects_modified_message() {
+    let mut engine = engine();
+    let vector = &ED25519[2];
+    let (_, public_key) = keys(vector);
+    assert_eq!(
+        engine.verify(&Ed25519, &public_key, [0xaf, 0x83], &[], &signature(vector)),
+        Err(ErrorKind::InvalidSignature)
+    );
+}
+
+#[test]
+fn ed25519ph_sign_and_verify() {
+    let mut engine = engine();
+    let (private_key, public_key) = keys(&ED25519PH);
+    let prehash = hex(ED25519PH.message);
+
+    let sig = engine
+        .sign_prehashed(&Ed25519, &private_key, &prehash, &[])
+        .unwrap();
+    assert_eq!(sig.to_bytes()[..], hex(ED25519PH.signature));
+    engine
+        .verify_prehashed(&Ed25519, &public_key, &prehash, &[], &signature(&ED25519PH))
+        .unwrap();
+
+    // The same signature does not verify with a context.
+    assert_eq!(
+        engine.verify_prehashed(
+            &Ed25519,
+            &public_key,
+            &prehash,
+            b"ctx",
+            &signature(&ED25519PH)
+        ),
+        Err(ErrorKind::InvalidSignature)
+    );
+}
+
+#[test]
+fn prehash_and_context_sizes_are_checked() {
+    let mut engine = engine();
+    let (private_key, _) = keys(&ED25519PH);
+    assert_eq!(
+        engine.sign_prehashed(&Ed25519, &private_key, [0; 32], &[]),
+        Err(ErrorKind::InvalidPrehashSize)
+    );
+    assert_eq!(
+        engine.sign_prehashed(&Ed25519, &private_key, [0; 64], &[0; MAX_CONTEXT_SIZE + 1]),
+        Err(ErrorKind::InvalidContext)
+    );
+    assert_eq!(
+        engine.sign(&Ed25519, &private_key, b"abc", b"ctx"),
+        Err(ErrorKind::InvalidContext)
+    );
+}
+
+#[test]
+fn generated_keys_round_trip() {
+    let mut engine = engine();
+    let (private_key, public_key) = engine.generate_key_pair(&Ed25519).unwrap();
+    let (other_private_key, _) = engine.generate_key_pair(&Ed25519).unwrap();
+    assert_ne!(private_key.to_bytes(), other_private_key.to_bytes());
+
+    let sig = engine
+        .sign(&Ed25519, &private_key, b"message", &[])
+        .unwrap();
+    engine
+        .verify(&Ed25519, &public_key, b"message", &[], &sig)
+        .unwrap();
+}
@@ -0,0 +1,147 @@
+//! RFC 5869 appendix A test vectors, with HMAC-SHA-256 from the software SHA-2 backend.
+
+#![cfg(feature = "sw-sha2")]
+
+mod common;
+
+use common::hex;
+use peripheral_traits::digest::{Sha2_256, Sha2_512};
+use peripheral_traits::hkdf::{self, Error};
+use peripheral_traits::hmac::{HmacContext, HmacEngine};
+use peripheral_traits::key::KeyHandle;
+use peripheral_traits::mac::{Hmac, MacInit};
+use peripheral_traits::sw::sha2::{Sha256Context, Sha2Engine};
+
+fn hmac_sha256() -> HmacContext<Sha256Context> {
+    HmacContext::new(Sha256Context::new())
+}
+
+struct Vector {
+    ikm: Vec<u8>,
+    salt: Vec<u8>,
+    info: Vec<u8>,
+    prk: &'static str,
+    okm: &'static str,
+}
+
+fn vectors() -> [Vector; 3] {
+    [
+        // A.1: basic test case.
+        Vector {
+            ikm: vec![0x0b; 22],
+            salt: (0x00..=0x0c).collect(),
+            info: (0xf0..=0xf9).collect(),
+            prk: "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+            okm: "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+                  34007208d5b887185865",
+        },
+        // A.2: longer inputs and outputs.
+        Vector {
+            ikm: (0x00..=0x4f).collect(),
+            salt: (0x60..=0xaf).collect(),
+            info: (0xb0..=0xff).collect(),
+            prk: "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+            okm: "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+                  59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+                  cc30c58179ec3e87c14c01d5c1f3434f1d87",
+        },
+        // A.3: zero-length salt and info.
+        Vector {
+            ikm: vec![0x0b; 22],
+            salt: vec![],
+            info: vec![],
+            prk: "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+            okm: "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d\
+                  9d201395faa4b61a96c8",
+        },
+    ]
+}
+
+#[test]
+fn extract_and_expand() {
+    for vector in vectors() {
+        let mut mac = hmac_sha256();
+        let mut prk = [0u8; 32];
+        let len = hkdf::extract(&mut mac, Some(&vector.salt), &vector.ikm, &mut prk).unwrap();
+        assert_eq!(prk[..len], hex(vector.prk));
+
+        let expected = hex(vector.okm);
+        let mut okm = vec![0u8; expected.len()];
+        hkdf::expand(&mut mac, KeyHandle::Raw(&prk), &vector.info, &mut okm).unwrap();
+        assert_eq!(okm, expected);
+    }
+}
+
+#[test]
+fn derive_matches_extract_and_expand() {
+    for vector in vectors() {
+        let expected = hex(vector.okm);
+        let mut okm = vec![0u8; expected.len()];
+        hkdf::derive(
+            &mut hmac_sha256(),
+            Some(&vector.salt),
+            &vector.ikm,
+            &vector.info,
+            &mut okm,
+        )
+        .unwrap();
+        assert_eq!(okm, expected);
+    }
+}
+
+#[test]
+fn missing_salt_is_hash_len_zeros() {
+    let vector = &vectors()[2];
+    let mut okm = [0u8; 42];
+    hkdf::derive(&mut hmac_sha256(), None, &vector.ikm, &[], &mut okm).unwrap();
+    assert_eq!(okm[..], hex(vector.okm));
+}
+
+#[test]
+fn works_through_a_mac_engine() {
+    let vector = &vectors()[1];
+    let mut engine = HmacEngine::new(Sha2Engine);
+    let mut mac = engine.init(Hmac(Sha2_256)).unwrap();
+    let mut okm = [0u8; 82];
+    hkdf::derive(
+        &mut mac,
+        Some(&vector.salt),
+        &vector.ikm,
+        &vector.info,
+        &mut okm,
+    )
+    .unwrap();
+    assert_eq!(okm[..], hex(vector.okm));
+}
+
+#[test]
+fn output_length_is_limited() {
+    assert_eq!(
+        hkdf::max_output_size::<HmacContext<Sha256Context>>(),
+        255 * 32
+    );
+
+    let mut okm = vec![0u8; 255 * 32];
+    hkdf::derive(&mut hmac_sha256(), None, b"ikm", b"", &mut okm).unwrap();
+
+    let mut okm = vec![0u8; 255 * 32 + 1];
+    assert_eq!(
+        hkdf::derive(&mut hmac_sha256(), None, b"ikm", b"", &mut okm),
+        Err(Error::OutputTooLong)
+    );
+    assert_eq!(
+        hkdf::expand(&mut hmac_sha256(), KeyHandle::Raw(&[0; 32]), b"", &mut okm),
+        Err(Error::OutputTooLong)
+    );
+}
+
+#[test]
+fn prk_buffer_must_hold_hash_len() {
+    let mut engine = HmacEngine::new(Sha2Engine);
+    let mut mac = engine.init(Hmac(Sha2_512)).unwrap();
+    let mut prk = [0u8; 32];
+    assert_eq!(
+        hkdf::extract(&mut mac, None, b"ikm", &mut prk),
+        Err(Error::BufferTooSmall)
+    );
+}
@@ -0,0 +1,291 @@
+//! RFC 4231 vectors for [`HmacContext`] over the software SHA-2 and SHA-3
+//! backends.
+//!
+//! RFC 4231 only covers SHA-2. The SHA-3 expectations are for the same inputs,
+//! computed with Python's `hmac` and `hashlib` modules.
+//!
+//! [`HmacContext`]: peripheral_traits::hmac::HmacContext
+
+#![cfg(any(feature = "sw-sha2", feature = "sw-sha3"))]
+
+mod common;
+
+use common::hex;
+use peripheral_traits::digest::{DigestAlgorithm, DigestInit};
+use peripheral_traits::hmac::HmacEngine;
+use peripheral_traits::mac::{Error, Hmac, Mac, MacInit};
+
+const CASE_6_DATA: &[u8] = b"Test Using Larger Than Block-Size Key - Hash Key First";
+const CASE_7_DATA: &[u8] = b"This is a test using a larger than block-size key and a larger \
+    than block-size data. The key needs to be hashed before being used by the HMAC algorithm.";
+
+/// Keys and data of RFC 4231 test cases 1 to 7.
+///
+/// Case 5 is checked through [`Mac::verify`] with the tag truncated to 128
+/// bits, the others through [`Mac::finalize`].
+fn rfc_4231() -> [(Vec<u8>, &'static [u8]); 7] {
+    [
+        (vec![0x0b; 20], b"Hi There"),
+        (b"Jefe".to_vec(), b"what do ya want for nothing?"),
+        (vec![0xaa; 20], &[0xdd; 50]),
+        ((0x01..=0x19).collect(), &[0xcd; 50]),
+        (vec![0x0c; 20], b"Test With Truncation"),
+        // 131 bytes, longer than the SHA-2, SHA3-384 and SHA3-512 blocks.
+        (vec![0xaa; 131], CASE_6_DATA),
+        (vec![0xaa; 131], CASE_7_DATA),
+    ]
+}
+
+/// Checks each `(key, data)` pair against the hex tag in `expected`.
+///
+/// Full-size tags are computed twice on the same context, to check that
+/// finalizing restarts the computation with the same key.
+fn check<E, A>(engine: E, algorithm: A, cases: &[(Vec<u8>, &[u8])], expected: &[&str])
+where
+    E: DigestInit<A>,
+    A: DigestAlgorithm + Copy,
+{
+    let mut engine = HmacEngine::new(engine);
+    for (i, ((key, data), expected)) in cases.iter().zip(expected).enumerate() {
+        let expected = hex(expected);
+        let mut hmac = MacInit::init(&mut engine, Hmac(algorithm)).unwrap();
+        hmac.set_key(key.as_slice().into()).unwrap();
+        hmac.update(data).unwrap();
+
+        if expected.len() < A::OUTPUT_SIZE {
+            assert_eq!(
+                hmac.verify(&expected).map_err(|e| e.kind()),
+                Ok(()),
+                "case {}",
+                i + 1
+            );
+            continue;
+        }
+
+        let mut tag = vec![0; A::OUTPUT_SIZE];
+        hmac.finalize(&mut tag).unwrap();
+        assert_eq!(tag, expected, "case {}", i + 1);
+
+        for byte in data.chunks(1) {
+            hmac.update(byte).unwrap();
+        }
+        hmac.finalize(&mut tag).unwrap();
+        assert_eq!(tag, expected, "case {}, second message", i + 1);
+    }
+}
+
+#[cfg(feature = "sw-sha2")]
+mod sha2 {
+    use super::*;
+    use peripheral_traits::digest::{Sha2_224, Sha2_256, Sha2_384, Sha2_512};
+    use peripheral_traits::mac::ErrorKind;
+    use peripheral_traits::sw::sha2::Sha2Engine;
+
+    #[test]
+    fn hmac_sha224() {
+        check(
+            Sha2Engine,
+            Sha2_224,
+            &rfc_4231(),
+            &[
+                "896fb1128abbdf196832107cd49df33f47b4b1169912ba4f53684b22",
+                "a30e01098bc6dbbf45690f3a7e9e6d0f8bbea2a39e6148008fd05e44",
+                "7fb3cb3588c6c1f6ffa9694d7d6ad2649365b0c1f65d69d1ec8333ea",
+                "6c11506874013cac6a2abc1bb382627cec6a90d86efc012de7afec5a",
+                "0e2aea68a90c8d37c988bcdb9fca6fa8",
+                "95e9a0db962095adaebe9b2d6f0dbce2d499f112f2d2b7273fa6870e",
+                "3a854166ac5d9f023f54d517d0b39dbd946770db9c2b95c9f6f565d1",
+            ],
+        );
+    }
+
+    #[test]
+    fn hmac_sha256() {
+        check(
+            Sha2Engine,
+            Sha2_256,
+            &rfc_4231(),
+            &[
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+                "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+                "a3b6167473100ee06e0c796c2955552b",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+                "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+            ],
+        );
+    }
+
+    #[test]
+    fn hmac_sha384() {
+        check(
+            Sha2Engine,
+            Sha2_384,
+            &rfc_4231(),
+            &[
+                "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59c\
+                 faea9ea9076ede7f4af152e8b2fa9cb6",
+                "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e\
+                 8e2240ca5e69e2c78b3239ecfab21649",
+                "88062608d3e6ad8a0aa2ace014c8a86f0aa635d947ac9febe83ef4e55966144b\
+                 2a5ab39dc13814b94e3ab6e101a34f27",
+                "3e8a69b7783c25851933ab6290af6ca77a9981480850009cc5577c6e1f573b4e\
+                 6801dd23c4a7d679ccf8a386c674cffb",
+                "3abf34c3503b2a23a46efc619baef897",
+                "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c6\
+                 0c2ef6ab4030fe8296248df163f44952",
+                "6617178e941f020d351e2f254e8fd32c602420feb0b8fb9adccebb82461e99c5\
+                 a678cc31e799176d3860e6110c46523e",
+            ],
+        );
+    }
+
+    #[test]
+    fn hmac_sha512() {
+        check(
+            Sha2Engine,
+            Sha2_512,
+            &rfc_4231(),
+            &[
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+                 daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554\
+                 9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+                "fa73b0089d56a284efb0f0756c890be9b1b5dbdd8ee81a3655f83e33b2279d39\
+                 bf3e848279a722c806b485a47e67c807b946a337bee8942674278859e13292fb",
+                "b0ba465637458c6990e5a8c5f61d4af7e576d97ff94b872de76f8050361ee3db\
+                 a91ca5c11aa25eb4d679275cc5788063a5f19741120c4f2de2adebeb10a298dd",
+                "415fad6271580a531d4179bc891d87a6",
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+                 6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+                "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944\
+                 b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+            ],
+        );
+    }
+
+    #[test]
+    fn truncated_tag_must_match() {
+        let [.., (key, data), _, _] = rfc_4231();
+        let mut tag = hex("a3b6167473100ee06e0c796c2955552b");
+        tag[15] ^= 1;
+
+        let mut engine = HmacEngine::new(Sha2Engine);
+        let mut hmac = engine.init(Hmac(Sha2_256)).unwrap();
+        hmac.set_key(key.as_slice().into()).unwrap();
+        hmac.update(data).unwrap();
+        assert_eq!(
+            hmac.verify(&tag).map_err(|e| e.kind()),
+            Err(ErrorKind::VerificationFailed)
+        );
+    }
+}
+
+#[cfg(feature = "sw-sha3")]
+mod sha3 {
+    use super::*;
+    use peripheral_traits::digest::{Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+    use peripheral_traits::sw::sha3::Sha3Engine;
+
+    /// The RFC 4231 cases, followed by a 147-byte key.
+    ///
+    /// The SHA3-224 and SHA3-256 blocks are 144 and 136 bytes, so the 131-byte
+    /// key of cases 6 and 7 is only padded for them, and the last case is
+    /// the first to hash the key.
+    fn cases() -> Vec<(Vec<u8>, &'static [u8])> {
+        let mut cases = rfc_4231().to_vec();
+        cases.push((vec![0xaa; 147], CASE_6_DATA));
+        cases
+    }
+
+    #[test]
+    fn hmac_sha3_224() {
+        check(
+            Sha3Engine,
+            Sha3_224,
+            &cases(),
+            &[
+                "3b16546bbc7be2706a031dcafd56373d9884367641d8c59af3c860f7",
+                "7fdb8dd88bd2f60d1b798634ad386811c2cfc85bfaf5d52bbace5e66",
+                "676cfc7d16153638780390692be142d2df7ce924b909c0c08dbfdc1a",
+                "a9d7685a19c4e0dbd9df2556cc8a7d2a7733b67625ce594c78270eeb",
+                "49fdd3abd005ebb8ae63fea946d1883c",
+                "b4a1f04c00287a9b7f6075b313d279b833bc8f75124352d05fb9995f",
+                "05d8cd6d00faea8d1eb68ade28730bbd3cbab6929f0a086b29cd62a0",
+                "b96d730c148c2daad8649d83defaa3719738d34775397b7571c38515",
+            ],
+        );
+    }
+
+    #[test]
+    fn hmac_sha3_256() {
+        check(
+            Sha3Engine,
+            Sha3_256,
+            &cases(),
+            &[
+                "ba85192310dffa96e2a3a40e69774351140bb7185e1202cdcc917589f95e16bb",
+                "c7d4072e788877ae3596bbb0da73b887c9171f93095b294ae857fbe2645e1ba5",
+                "84ec79124a27107865cedd8bd82da9965e5ed8c37b0ac98005a7f39ed58a4207",
+                "57366a45e2305321a4bc5aa5fe2ef8a921f6af8273d7fe7be6cfedb3f0aea6d7",
+                "6e02c64537fb118057abb7fb66a23b3c",
+                "ed73a374b96c005235f948032f09674a58c0ce555cfc1f223b02356560312c3b",
+                "65c5b06d4c3de32a7aef8763261e49adb6e2293ec8e7c61e8de61701fc63e123",
+                "a6072f86de52b38bb349fe84cd6d97fb6a37c4c0f62aae93981193a7229d3467",
+            ],
+        );
+    }
+
+    #[test]
+    fn hmac_sha3_384() {
+        check(
+            Sha3Engine,
+            Sha3_384,
+            &cases(),
+            &[
+                "68d2dcf7fd4ddd0a2240c8a437305f61fb7334cfb5d0226e1bc27dc10a2e723a\
+                 20d370b47743130e26ac7e3d532886bd",
+                "f1101f8cbf9766fd6764d2ed61903f21ca9b18f57cf3e1a23ca13508a93243ce\
+                 48c045dc007f26a21b3f5e0e9df4c20a",
+                "275cd0e661bb8b151c64d288f1f782fb91a8abd56858d72babb2d476f0458373\
+                 b41b6ab5bf174bec422e53fc3135ac6e",
+                "3a5d7a879702c086bc96d1dd8aa15d9c46446b95521311c606fdc4e308f4b984\
+                 da2d0f9449b3ba8425ec7fb8c31bc136",
+                "47c51ace1ffacffd7494724682615783",
+                "0fc19513bf6bd878037016706a0e57bc528139836b9a42c3d419e498e0e1fb96\
+                 16fd669138d33a1105e07c72b6953bcc",
+                "026fdf6b50741e373899c9f7d5406d4eb09fc6665636fc1a530029ddf5cf3ca5\
+                 a900edce01f5f61e2f408cdf2fd3e7e8",
+                "713dff0302c85086ec5ad0768dd65a13ddd79068d8d4c6212b712e4164944911\
+                 1480230044185a99103ed82004ddbfcc",
+            ],
+        );
+    }
+
+    #[test]
+    fn hmac_sha3_512() {
+        check(
+            Sha3Engine,
+            Sha3_512,
+            &cases(),
+            &[
+                "eb3fbd4b2eaab8f5c504bd3a41465aacec15770a7cabac531e482f860b5ec7ba\
+                 47ccb2c6f2afce8f88d22b6dc61380f23a668fd3888bb80537c0a0b86407689e",
+                "5a4bfeab6166427c7a3647b747292b8384537cdb89afb3bf5665e4c5e709350b\
+                 287baec921fd7ca0ee7a0c31d022a95e1fc92ba9d77df883960275beb4e62024",
+                "309e99f9ec075ec6c6d475eda1180687fcf1531195802a99b5677449a8625182\
+                 851cb332afb6a89c411325fbcbcd42afcb7b6e5aab7ea42c660f97fd8584bf03",
+                "b27eab1d6e8d87461c29f7f5739dd58e98aa35f8e823ad38c5492a2088fa0281\
+                 993bbfff9a0e9c6bf121ae9ec9bb09d84a5ebac817182ea974673fb133ca0d1d",
+                "0fa7475948f43f48ca0516671e18978c",
+                "00f751a9e50695b090ed6911a4b65524951cdc15a73a5d58bb55215ea2cd839a\
+                 c79d2b44a39bafab27e83fde9e11f6340b11d991b1b91bf2eee7fc872426c3a4",
+                "38a456a004bd10d32c9ab8336684112862c3db61adcca31829355eaf46fd5c73\
+                 d06a1f0d13fec9a652fb3811b577b1b1d1b9789f97ae5b83c6f44dfcf1d67eba",
+                "b14835c819a290efb010ace6d8568dc6b84de60bc49b004c3b13eda763589451\
+                 e5dd74292884d1bdce64e6b919dd61dc9c56a282a81c0bd14f1f365b49b83a5b",
+            ],
+        );
+    }
+}
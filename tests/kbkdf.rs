@@ -0,0 +1,333 @@
+//! SP 800-108 KDF tests with HMAC from the software SHA-2 backend.
+//!
+//! The HMAC-SHA224 vector is taken from the NIST CAVP `KDFCTR_gen.rsp` file.
+//! The other expectations were checked against independent implementations:
+//!
+//! - counter mode against OpenSSL 3 (`openssl kdf ... KBKDF`) and the
+//!   `KBKDFHMAC` of pyca/cryptography, which also covers the counter in the
+//!   middle of the fixed input;
+//! - feedback mode with a counter against OpenSSL 3, with both a non-zero and
+//!   a zero-length IV, as in the CAVP `FeedbackModeNOzeroiv.rsp` and
+//!   `FeedbackModewzeroiv.rsp` files;
+//! - feedback mode without a counter and double-pipeline mode, which neither
+//!   implements, by chaining HMACs from `openssl mac` as SP 800-108 sections
+//!   5.2 and 5.3 describe, covering the cases of the CAVP
+//!   `PipelineModewithCounter.rsp` and `PipelineModeWOCounterr.rsp` files.
+
+#![cfg(feature = "sw-sha2")]
+
+mod common;
+
+use common::hex;
+use peripheral_traits::hmac::HmacContext;
+use peripheral_traits::kbkdf::{
+    self, Counter, CounterLocation, CounterWidth, Error, FixedInput, Mode,
+};
+use peripheral_traits::key::{KeyHandle, SlotId};
+use peripheral_traits::mac::{Error as _, ErrorKind};
+use peripheral_traits::sw::sha2::{Sha224Context, Sha256Context, Sha384Context};
+
+const KEY: [u8; 48] = [0x42; 48];
+
+fn fixed() -> Vec<u8> {
+    (0..40).collect()
+}
+
+fn derive_sha256(mode: Mode<'_>, fixed_input: FixedInput<'_>, len: usize) -> Vec<u8> {
+    let mut mac = HmacContext::new(Sha256Context::new());
+    let mut out = vec![0u8; len];
+    kbkdf::derive(&mut mac, KeyHandle::Raw(&KEY), mode, fixed_input, &mut out).unwrap();
+    out
+}
+
+#[test]
+fn cavp_counter_hmac_sha224_after_fixed() {
+    // [PRF=HMAC_SHA224] [CTRLOCATION=AFTER_FIXED] [RLEN=8_BITS] COUNT=0
+    let ki = hex("ab56556b107a3a79fe084df0f1bb3ad049a6cc1490f20da4b3df282c");
+    let fixed = hex(
+        "7f50fc1f77c3ac752443154c1577d3c47b86fccffe82ff43aa1b91eeb5730d7e\
+         9e6aab78374d854aecb7143faba6b1eb90d3d9e7a2f6d78dd9a6c4a7",
+    );
+    let ko = hex("b8894c6133a46701909b5c8a84322dec");
+
+    let mut mac = HmacContext::new(Sha224Context::new());
+    let mut out = [0u8; 16];
+    kbkdf::derive(
+        &mut mac,
+        KeyHandle::Raw(&ki),
+        Mode::Counter(Counter::new(
+            CounterWidth::Bits8,
+            CounterLocation::AfterFixed,
+        )),
+        FixedInput::Raw(&fixed),
+        &mut out,
+    )
+    .unwrap();
+    assert_eq!(out[..], ko);
+}
+
+#[test]
+fn counter_hmac_sha384_multiple_blocks() {
+    let fixed = fixed();
+    let mut mac = HmacContext::new(Sha384Context::new());
+
+    let mut out = [0u8; 48];
+    kbkdf::derive(
+        &mut mac,
+        KeyHandle::Raw(&KEY),
+        Mode::Counter(Counter::new(
+            CounterWidth::Bits32,
+            CounterLocation::BeforeFixed,
+        )),
+        FixedInput::Raw(&fixed),
+        &mut out,
+    )
+    .unwrap();
+    assert_eq!(
+        out[..],
+        hex(
+            "a3864487943b583d8fbcf28428514355a8aa0ae6ab58b2c3ac351d85d27426be\
+             cc1d8c84b5acc97509bd227f55d1a108"
+        )
+    );
+
+    let mut out = [0u8; 100];
+    kbkdf::derive(
+        &mut mac,
+        KeyHandle::Raw(&KEY),
+        Mode::Counter(Counter::new(
+            CounterWidth::Bits24,
+            CounterLocation::MiddleFixed(7),
+        )),
+        FixedInput::Raw(&fixed),
+        &mut out,
+    )
+    .unwrap();
+    assert_eq!(
+        out[..],
+        hex(
+            "e7b9dab2de5d47d33375d54e5a33e957527e352e0e504a5dbfa8a9f2249f7d05\
+             1eccd718d57919d519c7f7d006c3cff84689839a676dbce7a8ccb291941ed5c8\
+             5b110bad044f84560de6d75e2d1d37eb9d8b5a95693699066474802d392bef9c\
+             ac231687"
+        )
+    );
+}
+
+#[test]
+fn counter_label_context_encoding() {
+    let out = derive_sha256(
+        Mode::Counter(Counter::new(
+            CounterWidth::Bits32,
+            CounterLocation::BeforeFixed,
+        )),
+        FixedInput::LabelContext {
+            label: b"label",
+            context: b"ctx",
+        },
+        48,
+    );
+    assert_eq!(
+        out,
+        hex(
+            "f59abc78b949d686eaf2337159f2810302b9d820d23fe5c95534454bd931cfa8\
+             05ecbca4b3e7da1f675cfff9cd1698aa"
+        )
+    );
+
+    // The same derivation with the fixed input formatted by hand.
+    let mut fixed = b"label\0ctx".to_vec();
+    fixed.extend_from_slice(&(48u32 * 8).to_be_bytes());
+    let raw = derive_sha256(
+        Mode::Counter(Counter::new(
+            CounterWidth::Bits32,
+            CounterLocation::BeforeFixed,
+        )),
+        FixedInput::Raw(&fixed),
+        48,
+    );
+    assert_eq!(out, raw);
+}
+
+const IV: [u8; 32] = [7; 32];
+
+fn feedback(iv: &[u8], counter: Option<Counter>) -> Vec<u8> {
+    derive_sha256(
+        Mode::Feedback { iv, counter },
+        FixedInput::Raw(&fixed()),
+        48,
+    )
+}
+
+fn double_pipeline(counter: Option<Counter>) -> Vec<u8> {
+    derive_sha256(
+        Mode::DoublePipeline { counter },
+        FixedInput::Raw(&fixed()),
+        48,
+    )
+}
+
+/// Counter after the chaining value, where SP 800-108 places it.
+fn after_iteration(width: CounterWidth) -> Option<Counter> {
+    Some(Counter::new(width, CounterLocation::BeforeFixed))
+}
+
+#[test]
+fn feedback_mode_with_iv() {
+    assert_eq!(
+        feedback(&IV, after_iteration(CounterWidth::Bits8)),
+        hex(
+            "337fd41281a85ea773f8e8d92e6ab38f48a5ee0b4357326cedd41c19bb51a121\
+             6fad3c995ee2ab672c4a33c6671301b7"
+        )
+    );
+    assert_eq!(
+        feedback(&IV, after_iteration(CounterWidth::Bits32)),
+        hex(
+            "580205ce478d4ecc3690671fc82dc9d814a8810a1ced8b8871f57fd24416fd93\
+             290cf91fea223d5ad2ba13d489325846"
+        )
+    );
+    assert_eq!(
+        feedback(&IV, None),
+        hex(
+            "8c8979b6c4fd4bc07405454da1310cba9565a0441c27b03514c5be42046226f8\
+             9840e0ab495d94ab9576c1652df429f0"
+        )
+    );
+}
+
+#[test]
+fn feedback_mode_with_zero_length_iv() {
+    assert_eq!(
+        feedback(&[], after_iteration(CounterWidth::Bits8)),
+        hex(
+            "65a5276a364ee76c861e15e6f3b537e1e168ce933a3b5fef040bfc2a760dff27\
+             84803c2e2ffab1bd51cd526c78e3d1a7"
+        )
+    );
+    assert_eq!(
+        feedback(&[], after_iteration(CounterWidth::Bits32)),
+        hex(
+            "38b7d2855cd58e942e4b5a0fd30a8543125947e28ba965af1bda5c558320fe35\
+             8a409b9aabbce773011bd80d88d27972"
+        )
+    );
+    // K(1) = PRF(FixedInput) is also A(1) of double-pipeline mode.
+    assert_eq!(
+        feedback(&[], None),
+        hex(
+            "68a7bb12fd81c1a5e83fd72351d7554f243d2ca141fd51b672657812aa367eb5\
+             1336f6bace28d86333908a0030719672"
+        )
+    );
+}
+
+#[test]
+fn double_pipeline_mode_with_counter() {
+    assert_eq!(
+        double_pipeline(after_iteration(CounterWidth::Bits8)),
+        hex(
+            "04929007b485c56045463cecf29572260eb54f5544acd29fa52c6c527a34d7e2\
+             d8fe39b4e735a8f2e7a1a94cb399f1bb"
+        )
+    );
+    assert_eq!(
+        double_pipeline(after_iteration(CounterWidth::Bits32)),
+        hex(
+            "a8abf1c2523ee365e081cb75e88400bf7a527e85d9c242c7d760462b1321cc59\
+             ad1f72446368e3a8518762e912f1eeed"
+        )
+    );
+}
+
+#[test]
+fn double_pipeline_mode_without_counter() {
+    assert_eq!(
+        double_pipeline(None),
+        hex(
+            "1336f6bace28d86333908a0030719672c0858ef98de97a8869d36ab4e30b444b\
+             aeb83a038ff3dd021194ab4a0328b381"
+        )
+    );
+}
+
+#[test]
+fn counter_overflow_is_rejected() {
+    let mode = Mode::Counter(Counter::new(
+        CounterWidth::Bits8,
+        CounterLocation::BeforeFixed,
+    ));
+    let mut mac = HmacContext::new(Sha256Context::new());
+
+    let mut out = vec![0u8; 255 * 32];
+    kbkdf::derive(
+        &mut mac,
+        KeyHandle::Raw(&KEY),
+        mode,
+        FixedInput::Raw(&[]),
+        &mut out,
+    )
+    .unwrap();
+
+    let mut out = vec![0u8; 255 * 32 + 1];
+    assert_eq!(
+        kbkdf::derive(
+            &mut mac,
+            KeyHandle::Raw(&KEY),
+            mode,
+            FixedInput::Raw(&[]),
+            &mut out
+        ),
+        Err(Error::OutputTooLong)
+    );
+}
+
+#[test]
+fn middle_fixed_needs_raw_fixed_input() {
+    let mut mac = HmacContext::new(Sha256Context::new());
+    let mut out = [0u8; 32];
+    let result = kbkdf::derive(
+        &mut mac,
+        KeyHandle::Raw(&KEY),
+        Mode::Counter(Counter::new(
+            CounterWidth::Bits8,
+            CounterLocation::MiddleFixed(2),
+        )),
+        FixedInput::LabelContext {
+            label: b"label",
+            context: b"ctx",
+        },
+        &mut out,
+    );
+    assert_eq!(result, Err(Error::InvalidCounterLocation));
+
+    let result = kbkdf::derive(
+        &mut mac,
+        KeyHandle::Raw(&KEY),
+        Mode::Counter(Counter::new(
+            CounterWidth::Bits8,
+            CounterLocation::MiddleFixed(41),
+        )),
+        FixedInput::Raw(&fixed()),
+        &mut out,
+    );
+    assert_eq!(result, Err(Error::InvalidCounterLocation));
+}
+
+#[test]
+fn key_slots_are_passed_to_the_mac() {
+    let mut mac = HmacContext::new(Sha256Context::new());
+    let mut out = [0u8; 32];
+    let result = kbkdf::derive(
+        &mut mac,
+        KeyHandle::Slot(SlotId(1)),
+        Mode::Counter(Counter::new(
+            CounterWidth::Bits32,
+            CounterLocation::BeforeFixed,
+        )),
+        FixedInput::Raw(&fixed()),
+        &mut out,
+    );
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::UnsupportedKeyHandle);
+}
@@ -0,0 +1,171 @@
+//! A reference key vault backend, enforcing the [`KeyPolicy`] of its slots,
+//! on top of the software P-256 backend.
+
+#![cfg(feature = "sw-ecdsa")]
+
+mod common;
+
+use common::CountingRng;
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use peripheral_traits::digest::Sha2_256;
+use peripheral_traits::ecdsa::{
+    EcdsaCurve, EcdsaKeyExport, EcdsaKeyGen, EcdsaKeyGenInSlot, EcdsaSign, EcdsaTypes,
+    EcdsaValidate, EcdsaVerify, ErrorKind, ErrorType, HashMarker, P256,
+};
+use peripheral_traits::key::{KeyPolicy, SlotId};
+use peripheral_traits::sw::ecdsa::EcdsaEngine;
+
+/// A vault of `N` slots. Private keys are only referred to by [`SlotId`].
+struct Vault<const N: usize> {
+    engine: EcdsaEngine<P256, CountingRng>,
+    slots: [Option<(SigningKey, KeyPolicy)>; N],
+}
+
+impl<const N: usize> Vault<N> {
+    fn new() -> Self {
+        Self {
+            engine: EcdsaEngine::new(CountingRng(0)),
+            slots: [const { None }; N],
+        }
+    }
+
+    /// The key in `slot`, if `policy` allows `allowed`.
+    fn key(
+        &self,
+        slot: SlotId,
+        allowed: impl Fn(&KeyPolicy) -> bool,
+    ) -> Result<&SigningKey, ErrorKind> {
+        let (key, policy) = self
+            .slots
+            .get(slot.0 as usize)
+            .and_then(Option::as_ref)
+            .ok_or(ErrorKind::Other)?;
+        if !allowed(policy) {
+            return Err(ErrorKind::PolicyViolation);
+        }
+        Ok(key)
+    }
+}
+
+impl<const N: usize> ErrorType for Vault<N> {
+    type Error = ErrorKind;
+}
+
+impl<const N: usize> EcdsaTypes for Vault<N> {
+    type PrivateKey = SlotId;
+    type PublicKey = VerifyingKey;
+    type Signature = Signature;
+    type Curve = P256;
+}
+
+impl<const N: usize> EcdsaKeyGenInSlot for Vault<N> {
+    fn generate_key_in_slot(
+        &mut self,
+        curve: &P256,
+        policy: KeyPolicy,
+    ) -> Result<(SlotId, VerifyingKey), ErrorKind> {
+        let index = self
+            .slots
+            .iter()
+            .position(Option::is_none)
+            .ok_or(ErrorKind::NoFreeSlot)?;
+        let (private_key, public_key) = self.engine.generate_key_pair(curve)?;
+        self.slots[index] = Some((private_key, policy));
+        Ok((SlotId(index as u32), public_key))
+    }
+}
+
+impl<const N: usize> EcdsaKeyExport for Vault<N> {
+    fn export_private_key(
+        &mut self,
+        _curve: &P256,
+        slot: SlotId,
+        out: &mut [u8],
+    ) -> Result<usize, ErrorKind> {
+        let key = self.key(slot, |policy| policy.export)?;
+        let out = out.get_mut(..P256::SCALAR_SIZE).ok_or(ErrorKind::Other)?;
+        out.copy_from_slice(&key.to_bytes());
+        Ok(out.len())
+    }
+}
+
+impl<const N: usize> EcdsaSign for Vault<N> {
+    type PrivateKey = SlotId;
+    type Curve = P256;
+    type Signature = Signature;
+
+    fn sign<H: HashMarker>(
+        &mut self,
+        curve: &P256,
+        private_key: &SlotId,
+        message_hash: impl AsRef<[u8]>,
+    ) -> Result<Signature, ErrorKind> {
+        let key = self.key(*private_key, |policy| policy.sign)?.clone();
+        self.engine.sign::<H>(curve, &key, message_hash)
+    }
+}
+
+#[test]
+fn generation_returns_a_slot_and_the_public_key() {
+    let mut vault = Vault::<2>::new();
+    let (slot, public_key) = vault
+        .generate_key_in_slot(&P256, KeyPolicy::SIGN_ONLY)
+        .unwrap();
+    let (other_slot, other_public_key) = vault
+        .generate_key_in_slot(&P256, KeyPolicy::SIGN_ONLY)
+        .unwrap();
+    assert_ne!(slot, other_slot);
+    assert_ne!(public_key, other_public_key);
+    assert_eq!(
+        vault.generate_key_in_slot(&P256, KeyPolicy::SIGN_ONLY),
+        Err(ErrorKind::NoFreeSlot)
+    );
+
+    // The slot signs for the public key returned with it.
+    let signature = vault.sign::<Sha2_256>(&P256, &slot, [0x5a; 32]).unwrap();
+    let mut engine = EcdsaEngine::<P256, _>::new(CountingRng(0));
+    let public_key = engine.validate(&P256, public_key).unwrap();
+    engine
+        .verify::<Sha2_256>(&P256, &public_key, [0x5a; 32], &signature)
+        .unwrap();
+    let other_public_key = engine.validate(&P256, other_public_key).unwrap();
+    assert_eq!(
+        engine.verify::<Sha2_256>(&P256, &other_public_key, [0x5a; 32], &signature),
+        Err(ErrorKind::InvalidSignature)
+    );
+}
+
+#[test]
+fn sign_only_keys_refuse_export() {
+    let mut vault = Vault::<1>::new();
+    let (slot, _) = vault
+        .generate_key_in_slot(&P256, KeyPolicy::SIGN_ONLY)
+        .unwrap();
+    let mut out = [0; 32];
+    assert_eq!(
+        vault.export_private_key(&P256, slot, &mut out),
+        Err(ErrorKind::PolicyViolation)
+    );
+    assert_eq!(out, [0; 32]);
+}
+
+#[test]
+fn keys_without_sign_refuse_signing() {
+    let policy = KeyPolicy {
+        sign: false,
+        agree: true,
+        export: true,
+    };
+    let mut vault = Vault::<1>::new();
+    let (slot, public_key) = vault.generate_key_in_slot(&P256, policy).unwrap();
+    assert_eq!(
+        vault.sign::<Sha2_256>(&P256, &slot, [0x5a; 32]),
+        Err(ErrorKind::PolicyViolation)
+    );
+
+    // Export is allowed, and gives the key of the slot.
+    let mut out = [0; 32];
+    assert_eq!(vault.export_private_key(&P256, slot, &mut out), Ok(32));
+    let private_key = SigningKey::from_slice(&out).unwrap();
+    assert_eq!(*private_key.verifying_key(), public_key);
+}
@@ -0,0 +1,143 @@
+use peripheral_traits::mac::{ct_eq, verify_tag, ErrorKind, MIN_TAG_SIZE};
+
+const COMPUTED: [u8; 16] = [
+    0xa3, 0xb6, 0x16, 0x74, 0x73, 0x10, 0x0e, 0xe0, 0x6e, 0x0c, 0x79, 0x6c, 0x29, 0x55, 0x55, 0x2b,
+];
+
+#[test]
+fn ct_eq_compares_contents_and_length() {
+    assert!(ct_eq(&COMPUTED, &COMPUTED));
+    assert!(ct_eq(&[], &[]));
+    assert!(!ct_eq(&COMPUTED, &COMPUTED[..15]));
+
+    let mut other = COMPUTED;
+    other[15] ^= 1;
+    assert!(!ct_eq(&COMPUTED, &other));
+}
+
+#[test]
+fn verify_tag_accepts_full_and_truncated_tags() {
+    assert_eq!(verify_tag(&COMPUTED, &COMPUTED, MIN_TAG_SIZE), Ok(()));
+    assert_eq!(verify_tag(&COMPUTED, &COMPUTED[..12], MIN_TAG_SIZE), Ok(()));
+}
+
+#[test]
+fn verify_tag_rejects_short_long_and_wrong_tags() {
+    assert_eq!(
+        verify_tag(&COMPUTED, &COMPUTED[..11], MIN_TAG_SIZE),
+        Err(ErrorKind::TagTooShort)
+    );
+    assert_eq!(
+        verify_tag(&COMPUTED, &[], MIN_TAG_SIZE),
+        Err(ErrorKind::TagTooShort)
+    );
+
+    let mut long = [0u8; 17];
+    long[..16].copy_from_slice(&COMPUTED);
+    assert_eq!(
+        verify_tag(&COMPUTED, &long, MIN_TAG_SIZE),
+        Err(ErrorKind::VerificationFailed)
+    );
+
+    let mut wrong = COMPUTED;
+    wrong[0] ^= 0x80;
+    assert_eq!(
+        verify_tag(&COMPUTED, &wrong[..12], MIN_TAG_SIZE),
+        Err(ErrorKind::VerificationFailed)
+    );
+}
+
+#[cfg(feature = "sw-sha2")]
+mod hmac {
+    use peripheral_traits::digest::Sha2_256;
+    use peripheral_traits::hmac::HmacEngine;
+    use peripheral_traits::mac::{Error, ErrorKind, Hmac, Mac, MacInit};
+    use peripheral_traits::sw::sha2::Sha2Engine;
+
+    // RFC 4231 test case 5, HMAC-SHA-256 truncated to 128 bits.
+    const KEY: [u8; 20] = [0x0c; 20];
+    const DATA: &[u8] = b"Test With Truncation";
+    const TAG_128: [u8; 16] = super::COMPUTED;
+
+    fn verify(tag: &[u8]) -> Result<(), ErrorKind> {
+        let mut engine = HmacEngine::new(Sha2Engine);
+        let mut hmac = engine.init(Hmac(Sha2_256)).unwrap();
+        hmac.set_key(KEY.as_slice().into()).unwrap();
+        hmac.update(DATA).unwrap();
+        hmac.verify(tag).map_err(|e| e.kind())
+    }
+
+    #[test]
+    fn full_tag_verifies() {
+        let mut engine = HmacEngine::new(Sha2Engine);
+        let mut hmac = engine.init(Hmac(Sha2_256)).unwrap();
+        hmac.set_key(KEY.as_slice().into()).unwrap();
+        hmac.update(DATA).unwrap();
+        let mut tag = [0u8; 32];
+        hmac.finalize(&mut tag).unwrap();
+        assert_eq!(tag[..16], TAG_128);
+
+        assert_eq!(verify(&tag), Ok(()));
+    }
+
+    #[test]
+    fn truncated_tags_verify() {
+        assert_eq!(verify(&TAG_128), Ok(()));
+        assert_eq!(verify(&TAG_128[..12]), Ok(()));
+    }
+
+    #[test]
+    fn too_short_tag_is_rejected() {
+        assert_eq!(verify(&TAG_128[..11]), Err(ErrorKind::TagTooShort));
+    }
+
+    #[test]
+    fn wrong_truncated_tag_is_rejected() {
+        let mut tag = TAG_128;
+        tag[11] ^= 1;
+        assert_eq!(verify(&tag[..12]), Err(ErrorKind::VerificationFailed));
+    }
+}
+
+#[cfg(feature = "sw-aes-cmac")]
+mod cmac {
+    use peripheral_traits::mac::{AesCmac, ErrorKind, Mac, MacInit};
+    use peripheral_traits::sw::cmac::AesCmacEngine;
+
+    // RFC 4493 example 2.
+    const KEY: [u8; 16] = [
+        0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f,
+        0x3c,
+    ];
+    const MESSAGE: [u8; 16] = [
+        0x6b, 0xc1, 0xbe, 0xe2, 0x2e, 0x40, 0x9f, 0x96, 0xe9, 0x3d, 0x7e, 0x11, 0x73, 0x93, 0x17,
+        0x2a,
+    ];
+    const TAG: [u8; 16] = [
+        0x07, 0x0a, 0x16, 0xb4, 0x6b, 0x4d, 0x41, 0x44, 0xf7, 0x9b, 0xdd, 0x9d, 0xd0, 0x4a, 0x28,
+        0x7c,
+    ];
+
+    fn verify(tag: &[u8]) -> Result<(), ErrorKind> {
+        let mut engine = AesCmacEngine;
+        let mut cmac = engine.init(AesCmac).unwrap();
+        cmac.set_key(KEY.as_slice().into()).unwrap();
+        cmac.update(&MESSAGE).unwrap();
+        cmac.verify(tag)
+    }
+
+    #[test]
+    fn full_and_truncated_tags_verify() {
+        assert_eq!(verify(&TAG), Ok(()));
+        assert_eq!(verify(&TAG[..12]), Ok(()));
+    }
+
+    #[test]
+    fn too_short_and_wrong_tags_are_rejected() {
+        assert_eq!(verify(&TAG[..8]), Err(ErrorKind::TagTooShort));
+
+        let mut tag = TAG;
+        tag[15] ^= 1;
+        assert_eq!(verify(&tag), Err(ErrorKind::VerificationFailed));
+    }
+}
@@ -0,0 +1,193 @@
+//! Tests of the measurement registers, with the software SHA-256 backend.
+
+#![cfg(feature = "sw-sha2")]
+
+mod common;
+
+use common::hex;
+use peripheral_traits::measurement::{
+    extend_all, Bank, Error, ErrorKind, MeasurementBank, ResetPolicy,
+};
+use peripheral_traits::sw::sha2::{Sha256Context, Sha384Context};
+
+const ZERO: [u8; 32] = [0; 32];
+/// `SHA-256(0^32 || "boot loader")`.
+const BOOT_LOADER: &str = "1c20c5e6592957c18f4f1c0e5aac0428f0eb4fb5964b39a9521ad2c0e199ef94";
+/// `SHA-256(BOOT_LOADER || "kernel")`.
+const KERNEL: &str = "718674cb2a700d1a232822db43a6e3c4046f39c8efc7acde4411b19f15ca1d52";
+/// `SHA-256(0^32 || "config")`.
+const CONFIG: &str = "9e93fdf6b23944453389c1f014e66aad5bdef8dd11066dd40ddacdc9539bf551";
+
+fn bank() -> MeasurementBank<Sha256Context, 3> {
+    MeasurementBank::new(
+        Sha256Context::new(),
+        [
+            ResetPolicy::OnBoot,
+            ResetPolicy::OnBoot,
+            ResetPolicy::Resettable,
+        ],
+    )
+}
+
+#[test]
+fn extend_hashes_old_value_and_measurement() {
+    let mut bank = bank();
+    for index in 0..3 {
+        assert_eq!(bank.read(index).unwrap().as_ref(), ZERO);
+    }
+
+    bank.extend(0, b"boot loader").unwrap();
+    assert_eq!(bank.read(0).unwrap().as_ref(), hex(BOOT_LOADER));
+    bank.extend(0, b"kernel").unwrap();
+    assert_eq!(bank.read(0).unwrap().as_ref(), hex(KERNEL));
+    bank.extend(2, b"config").unwrap();
+    assert_eq!(bank.read(2).unwrap().as_ref(), hex(CONFIG));
+    assert_eq!(bank.read(1).unwrap().as_ref(), ZERO);
+
+    assert_eq!(bank.extend(3, b"x"), Err(Error::InvalidIndex));
+    assert_eq!(bank.read(3), Err(Error::InvalidIndex));
+}
+
+#[test]
+fn locked_register_rejects_extend_and_reset() {
+    let mut bank = bank();
+    bank.extend(2, b"config").unwrap();
+    bank.lock(2).unwrap();
+    assert_eq!(bank.is_locked(2), Ok(true));
+    assert_eq!(bank.is_locked(0), Ok(false));
+
+    assert_eq!(bank.extend(2, b"other"), Err(Error::Locked));
+    assert_eq!(bank.reset(2), Err(Error::Locked));
+    assert_eq!(bank.read(2).unwrap().as_ref(), hex(CONFIG));
+
+    // Locking again changes nothing; other registers are unaffected.
+    bank.lock(2).unwrap();
+    bank.extend(0, b"boot loader").unwrap();
+    assert_eq!(bank.lock(3), Err(Error::InvalidIndex));
+}
+
+#[test]
+fn only_resettable_registers_reset() {
+    let mut bank = bank();
+    bank.extend(0, b"boot loader").unwrap();
+    bank.extend(2, b"config").unwrap();
+
+    assert_eq!(bank.reset(0), Err(Error::NotResettable));
+    assert_eq!(bank.read(0).unwrap().as_ref(), hex(BOOT_LOADER));
+    assert_eq!(bank.reset(0).unwrap_err().kind(), ErrorKind::NotResettable);
+
+    bank.reset(2).unwrap();
+    assert_eq!(bank.read(2).unwrap().as_ref(), ZERO);
+    assert_eq!(bank.reset(3), Err(Error::InvalidIndex));
+}
+
+#[test]
+fn banks_reset_through_the_trait() {
+    let mut sha256 = bank();
+    let mut sha384 = MeasurementBank::new(
+        Sha384Context::new(),
+        [
+            ResetPolicy::OnBoot,
+            ResetPolicy::Resettable,
+            ResetPolicy::Resettable,
+        ],
+    );
+    let banks: [&mut dyn Bank; 2] = [&mut sha256, &mut sha384];
+    for bank in banks {
+        bank.extend(2, b"config").unwrap();
+        bank.reset(2).unwrap();
+        assert!(bank.read(2).unwrap().iter().all(|&b| b == 0));
+
+        bank.extend(0, b"boot loader").unwrap();
+        assert_eq!(bank.reset(0), Err(ErrorKind::NotResettable));
+        assert!(bank.read(0).unwrap().iter().any(|&b| b != 0));
+
+        bank.extend(2, b"config").unwrap();
+        bank.lock(2).unwrap();
+        assert_eq!(bank.reset(2), Err(ErrorKind::Locked));
+        assert_eq!(bank.reset(3), Err(ErrorKind::InvalidIndex));
+    }
+    assert_eq!(sha256.read(2).unwrap().as_ref(), hex(CONFIG));
+}
+
+#[test]
+fn extend_all_checks_every_bank_first() {
+    let mut sha256 = bank();
+    let mut sha384 = MeasurementBank::new(Sha384Context::new(), [ResetPolicy::OnBoot; 3]);
+
+    extend_all(&mut [&mut sha256, &mut sha384], 0, b"boot loader").unwrap();
+    assert_eq!(sha256.read(0).unwrap().as_ref(), hex(BOOT_LOADER));
+    assert_eq!(Bank::read(&sha384, 0).unwrap().len(), 48);
+    assert_ne!(Bank::read(&sha384, 0).unwrap(), [0; 48]);
+
+    // A lock in one bank keeps the other bank unchanged.
+    sha384.lock(1).unwrap();
+    assert_eq!(
+        extend_all(&mut [&mut sha256, &mut sha384], 1, b"kernel"),
+        Err(ErrorKind::Locked)
+    );
+    assert_eq!(sha256.read(1).unwrap().as_ref(), ZERO);
+}
+
+/// Quotes signed with the software P-256 backend.
+#[cfg(feature = "sw-ecdsa")]
+mod quote {
+    use super::*;
+    use crate::common::CountingRng;
+    use peripheral_traits::digest::Sha2_256;
+    use peripheral_traits::ecdsa::{EcdsaKeyGen, EcdsaValidate, EcdsaVerify, P256};
+    use peripheral_traits::measurement::QuoteError;
+    use peripheral_traits::sw::ecdsa::EcdsaEngine;
+
+    /// `SHA-256("nonce" || 0u32 || KERNEL || 2u32 || CONFIG)`.
+    const QUOTE: &str = "2bb1511dc4fdaa78862a16518e20e4d6d198ea4f1c4355236f6d0dc2b62620a9";
+
+    #[test]
+    fn quote_covers_nonce_indices_and_values() {
+        let mut engine = EcdsaEngine::<P256, _>::new(CountingRng(0));
+        let (private_key, public_key) = engine.generate_key_pair(&P256).unwrap();
+        let public_key = engine.validate(&P256, public_key).unwrap();
+
+        let mut bank = bank();
+        bank.extend(0, b"boot loader").unwrap();
+        bank.extend(0, b"kernel").unwrap();
+        bank.extend(1, b"not quoted").unwrap();
+        bank.extend(2, b"config").unwrap();
+
+        let quote = bank
+            .quote(&mut engine, &[0, 2], b"nonce", &P256, &private_key)
+            .unwrap();
+        assert_eq!(quote.digest.as_ref(), hex(QUOTE));
+        engine
+            .verify::<Sha2_256>(&P256, &public_key, quote.digest, &quote.signature)
+            .unwrap();
+
+        // Register 1 is not covered; a different nonce gives a different quote.
+        bank.extend(1, b"changed").unwrap();
+        let again = bank
+            .quote(&mut engine, &[0, 2], b"nonce", &P256, &private_key)
+            .unwrap();
+        assert_eq!(again.digest, quote.digest);
+        let other = bank
+            .quote(&mut engine, &[0, 2], b"other", &P256, &private_key)
+            .unwrap();
+        assert_ne!(other.digest, quote.digest);
+    }
+
+    #[test]
+    fn quote_rejects_invalid_selections() {
+        let mut engine = EcdsaEngine::<P256, _>::new(CountingRng(0));
+        let (private_key, _) = engine.generate_key_pair(&P256).unwrap();
+        let mut bank = bank();
+
+        for selection in [&[][..], &[2, 0], &[0, 0], &[0, 3]] {
+            let error = bank
+                .quote(&mut engine, selection, b"nonce", &P256, &private_key)
+                .unwrap_err();
+            assert!(matches!(
+                error,
+                QuoteError::Measurement(Error::InvalidSelection | Error::InvalidIndex)
+            ));
+        }
+    }
+}
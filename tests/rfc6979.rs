@@ -0,0 +1,153 @@
+//! RFC 6979 appendix A.2.5 to A.2.7 nonces, with HMAC from the software SHA-2 backend.
+
+#![cfg(feature = "sw-sha2")]
+
+mod common;
+
+use common::hex;
+use peripheral_traits::digest::Digest;
+use peripheral_traits::ecdsa::{EcdsaCurve, P256, P384, P521};
+use peripheral_traits::hmac::HmacContext;
+use peripheral_traits::rfc6979::{self, Error, NonceGenerator};
+use peripheral_traits::sw::sha2::{Sha256Context, Sha384Context, Sha512Context};
+
+fn hash<D: Digest>(mut digest: D, message: &[u8]) -> Vec<u8> {
+    digest.update(message).unwrap();
+    digest.finalize().unwrap().as_ref().to_vec()
+}
+
+fn generate_k<C: EcdsaCurve, D: Digest + Clone>(
+    digest: D,
+    private_key: &str,
+    message: &[u8],
+    extra_entropy: &[u8],
+) -> Vec<u8> {
+    let message_hash = hash(digest.clone(), message);
+    let mut mac = HmacContext::new(digest);
+    let mut k = vec![0u8; C::SCALAR_SIZE];
+    rfc6979::generate_k::<C, _>(
+        &mut mac,
+        &hex(private_key),
+        &message_hash,
+        extra_entropy,
+        &mut k,
+    )
+    .unwrap();
+    k
+}
+
+const P256_KEY: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+const P384_KEY: &str = "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d8\
+                        96d5724e4c70a825f872c9ea60d2edf5";
+const P521_KEY: &str = "00fad06daa62ba3b25d2fb40133da757205de67f5bb0018fee8c86e1b68c7e75\
+                        caa896eb32f1f47c70855836a6d16fcc1466f6d8fbec67db89ec0c08b0e996b8\
+                        3538";
+
+#[test]
+fn p256_sha256() {
+    assert_eq!(
+        generate_k::<P256, _>(Sha256Context::new(), P256_KEY, b"sample", &[]),
+        hex("a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60")
+    );
+    assert_eq!(
+        generate_k::<P256, _>(Sha256Context::new(), P256_KEY, b"test", &[]),
+        hex("d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0")
+    );
+}
+
+#[test]
+fn p384_sha384() {
+    assert_eq!(
+        generate_k::<P384, _>(Sha384Context::new(), P384_KEY, b"sample", &[]),
+        hex(
+            "94ed910d1a099dad3254e9242ae85abde4ba15168eaf0ca87a555fd56d10fbca\
+             2907e3e83ba95368623b8c4686915cf9"
+        )
+    );
+    assert_eq!(
+        generate_k::<P384, _>(Sha384Context::new(), P384_KEY, b"test", &[]),
+        hex(
+            "015ee46a5bf88773ed9123a5ab0807962d193719503c527b031b4c2d225092ad\
+             a71f4a459bc0da98adb95837db8312ea"
+        )
+    );
+}
+
+#[test]
+fn p521_sha512() {
+    assert_eq!(
+        generate_k::<P521, _>(Sha512Context::new(), P521_KEY, b"sample", &[]),
+        hex(
+            "01dae2ea071f8110dc26882d4d5eae0621a3256fc8847fb9022e2b7d28e6f101\
+             98b1574fdd03a9053c08a1854a168aa5a57470ec97dd5ce090124ef52a2f7ecb\
+             ffd3"
+        )
+    );
+    assert_eq!(
+        generate_k::<P521, _>(Sha512Context::new(), P521_KEY, b"test", &[]),
+        hex(
+            "016200813020ec986863bedfc1b121f605c1215645018aea1a7b215a564de9eb\
+             1b38a67aa1128b80ce391c4fb71187654aaa3431027bfc7f395766ca988c964d\
+             c56d"
+        )
+    );
+}
+
+#[test]
+fn hash_longer_and_shorter_than_the_order() {
+    // The same RFC key and message with SHA-512 on P-256 and SHA-256 on P-384.
+    assert_eq!(
+        generate_k::<P256, _>(Sha512Context::new(), P256_KEY, b"sample", &[]),
+        hex("5fa81c63109badb88c1f367b47da606da28cad69aa22c4fe6ad7df73a7173aa5")
+    );
+    assert_eq!(
+        generate_k::<P384, _>(Sha256Context::new(), P384_KEY, b"sample", &[]),
+        hex(
+            "180ae9f9aec5438a44bc159a1fcb277c7be54fa20e7cf404b490650a8acc414e\
+             375572342863c899f9f2edf9747a9b60"
+        )
+    );
+}
+
+#[test]
+fn hedged_nonce_depends_on_the_entropy() {
+    let deterministic = generate_k::<P256, _>(Sha256Context::new(), P256_KEY, b"sample", &[]);
+    let hedged = generate_k::<P256, _>(Sha256Context::new(), P256_KEY, b"sample", &[1; 32]);
+    let other = generate_k::<P256, _>(Sha256Context::new(), P256_KEY, b"sample", &[2; 32]);
+    assert_ne!(hedged, deterministic);
+    assert_ne!(hedged, other);
+}
+
+#[test]
+fn further_candidates_differ() {
+    let message_hash = hash(Sha256Context::new(), b"sample");
+    let mut mac = HmacContext::new(Sha256Context::new());
+    let mut generator =
+        NonceGenerator::<_, P256>::new(&mut mac, &hex(P256_KEY), &message_hash, &[]).unwrap();
+    let mut first = [0u8; 32];
+    let mut second = [0u8; 32];
+    generator.next_k(&mut first).unwrap();
+    generator.next_k(&mut second).unwrap();
+    assert_eq!(
+        first[..],
+        hex("a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60")
+    );
+    assert_ne!(first, second);
+}
+
+#[test]
+fn private_key_must_be_a_valid_scalar() {
+    let mut mac = HmacContext::new(Sha256Context::new());
+    let mut k = [0u8; 32];
+    for private_key in [
+        vec![0u8; 32],
+        vec![0xff; 32],
+        vec![1; 31],
+        P256::ORDER.to_vec(),
+    ] {
+        assert_eq!(
+            rfc6979::generate_k::<P256, _>(&mut mac, &private_key, &[0; 32], &[], &mut k),
+            Err(Error::InvalidPrivateKey)
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Tests of the RustCrypto adapters in both directions.
+
+#![cfg(all(feature = "rustcrypto", feature = "sw-sha2"))]
+
+mod common;
+
+use common::hex;
+use peripheral_traits::digest::{Digest, DigestInit, Sha2_256, Sha2_512};
+use peripheral_traits::hmac::HmacContext;
+use peripheral_traits::key::{KeyHandle, SlotId};
+use peripheral_traits::mac::{ErrorKind, HmacSha2_256, HmacSha2_512, Mac};
+use peripheral_traits::rustcrypto::{Hasher, RustCryptoDigest, RustCryptoEngine, RustCryptoMac};
+use peripheral_traits::sw::sha2::{Sha256Context, Sha2Engine, Sha512Context};
+
+fn message(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 13 + 1) as u8).collect()
+}
+
+#[test]
+fn rustcrypto_hasher_as_digest() {
+    let mut digest = RustCryptoDigest::<sha2::Sha256, Sha2_256>::new(sha2::Sha256::default());
+    digest.update(b"abc").unwrap();
+    assert_eq!(
+        digest.finalize().unwrap().as_ref(),
+        hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+
+    // Finalizing resets the computation, as does reset.
+    digest.update(b"discarded").unwrap();
+    digest.reset().unwrap();
+    digest.update(b"abc").unwrap();
+    assert_eq!(
+        digest.finalize().unwrap().as_ref(),
+        hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+
+    let mut engine = RustCryptoEngine::<sha2::Sha512>::default();
+    let mut sw = Sha2Engine;
+    for len in [0, 111, 112, 1000] {
+        let mut digest = engine.init(Sha2_512).unwrap();
+        digest.update(&message(len)).unwrap();
+        let mut expected = sw.init(Sha2_512).unwrap();
+        expected.update(&message(len)).unwrap();
+        assert_eq!(digest.finalize().unwrap(), expected.finalize().unwrap());
+    }
+}
+
+/// RFC 4231 test case 2.
+const KEY: &[u8] = b"Jefe";
+const DATA: &[u8] = b"what do ya want for nothing?";
+const HMAC_SHA256: &str = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+const HMAC_SHA512: &str = "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554\
+                           9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737";
+
+#[test]
+fn rustcrypto_mac_as_mac() {
+    let mut mac = RustCryptoMac::<hmac::Hmac<sha2::Sha256>, HmacSha2_256>::new();
+    mac.set_key(KeyHandle::Raw(KEY)).unwrap();
+    mac.update(DATA).unwrap();
+    let mut tag = [0; 32];
+    mac.finalize(&mut tag).unwrap();
+    assert_eq!(tag[..], hex(HMAC_SHA256));
+
+    // The key is kept: the next message starts right away.
+    mac.update(b"discarded").unwrap();
+    mac.reset().unwrap();
+    mac.update(DATA).unwrap();
+    mac.verify(&tag).unwrap();
+    mac.update(DATA).unwrap();
+    mac.verify(&tag[..12]).unwrap();
+
+    mac.update(DATA).unwrap();
+    assert_eq!(mac.verify(&tag[..11]), Err(ErrorKind::TagTooShort));
+    mac.update(b"what do ya want for something?").unwrap();
+    assert_eq!(mac.verify(&tag), Err(ErrorKind::VerificationFailed));
+    mac.update(DATA).unwrap();
+    assert_eq!(
+        mac.finalize(&mut tag[..31]),
+        Err(ErrorKind::InvalidOutputSize)
+    );
+
+    let mut mac = RustCryptoMac::<hmac::Hmac<sha2::Sha512>, HmacSha2_512>::new();
+    mac.set_key(KeyHandle::Raw(KEY)).unwrap();
+    mac.update(DATA).unwrap();
+    mac.verify(&hex(HMAC_SHA512)).unwrap();
+
+    // A key longer than the block size, against this crate's HMAC.
+    let key = message(200);
+    let mut mac = RustCryptoMac::<hmac::Hmac<sha2::Sha512>, HmacSha2_512>::new();
+    let mut sw = HmacContext::new(Sha512Context::new());
+    mac.set_key(KeyHandle::Raw(&key)).unwrap();
+    sw.set_key(KeyHandle::Raw(&key)).unwrap();
+    mac.update(&message(300)).unwrap();
+    sw.update(&message(300)).unwrap();
+    let mut expected = [0; 64];
+    sw.finalize(&mut expected).unwrap();
+    mac.verify(&expected).unwrap();
+}
+
+#[test]
+fn rustcrypto_mac_needs_a_raw_key() {
+    let mut mac = RustCryptoMac::<hmac::Hmac<sha2::Sha256>, HmacSha2_256>::new();
+    assert_eq!(mac.update(DATA), Err(ErrorKind::NotInitialized));
+    assert_eq!(mac.reset(), Err(ErrorKind::NotInitialized));
+    assert_eq!(mac.verify(&[0; 32]), Err(ErrorKind::NotInitialized));
+    assert_eq!(
+        mac.set_key(KeyHandle::Slot(SlotId(1))),
+        Err(ErrorKind::UnsupportedKeyHandle)
+    );
+    assert_eq!(mac.update(DATA), Err(ErrorKind::NotInitialized));
+}
+
+#[test]
+fn digest_as_rustcrypto_hasher() {
+    use sha2::Digest as _;
+
+    for len in [0, 55, 56, 64, 1000] {
+        let message = message(len);
+        assert_eq!(
+            Hasher::<Sha256Context>::digest(&message),
+            sha2::Sha256::digest(&message)
+        );
+        assert_eq!(
+            Hasher::<Sha512Context>::digest(&message),
+            sha2::Sha512::digest(&message)
+        );
+    }
+
+    let mut hasher = Hasher::new(Sha256Context::new());
+    hasher.update(b"discarded");
+    sha2::Digest::reset(&mut hasher);
+    hasher.update(b"ab");
+    hasher.update(b"c");
+    assert_eq!(
+        hasher.finalize_reset()[..],
+        hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+}
+
+/// The software ECDSA engine behind the RustCrypto signature traits, checked
+/// against the `p256` crate and RFC 6979 appendix A.2.5.
+#[cfg(feature = "sw-ecdsa")]
+mod ecdsa {
+    use super::hex;
+    use crate::common::CountingRng;
+    use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use peripheral_traits::digest::Sha2_256;
+    use peripheral_traits::ecdsa::message::{sign_message, verify_message};
+    use peripheral_traits::ecdsa::{EcdsaValidate, ErrorKind as EcdsaErrorKind, P256};
+    use peripheral_traits::rustcrypto::{EcdsaSigner, EcdsaVerifier, RustCryptoEcdsa};
+    use peripheral_traits::sw::ecdsa::EcdsaEngine;
+    use peripheral_traits::sw::sha2::Sha256Context;
+    use signature::hazmat::{PrehashSigner, PrehashVerifier};
+    use signature::{Signer, Verifier};
+
+    const KEY: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+    const SAMPLE: &str = "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716\
+                          f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8";
+
+    #[test]
+    fn engine_as_rustcrypto_signer_and_verifier() {
+        let private_key = SigningKey::from_slice(&hex(KEY)).unwrap();
+        let public_key = *private_key.verifying_key();
+
+        let signer = EcdsaSigner::<_, Sha2_256, Sha256Context>::new(
+            EcdsaEngine::<P256, _>::new(CountingRng(0)),
+            P256,
+            private_key.clone(),
+        );
+        let signature: Signature = signer.sign(b"sample");
+        assert_eq!(signature.to_bytes()[..], hex(SAMPLE));
+        assert_eq!(signature, private_key.sign(b"sample"));
+        public_key.verify(b"sample", &signature).unwrap();
+
+        let prehash = [0x5a; 32];
+        let signature: Signature = signer.sign_prehash(&prehash).unwrap();
+        public_key.verify_prehash(&prehash, &signature).unwrap();
+
+        let mut engine = EcdsaEngine::<P256, _>::new(CountingRng(0));
+        let validated = engine.validate(&P256, public_key).unwrap();
+        let verifier = EcdsaVerifier::<_, Sha2_256, Sha256Context>::new(engine, P256, validated);
+        verifier
+            .verify(b"sample", &private_key.sign(b"sample"))
+            .unwrap();
+        assert!(verifier
+            .verify(b"sample!", &private_key.sign(b"sample"))
+            .is_err());
+        verifier.verify_prehash(&prehash, &signature).unwrap();
+    }
+
+    #[test]
+    fn rustcrypto_keys_as_backend() {
+        let private_key = SigningKey::from_slice(&hex(KEY)).unwrap();
+        let mut backend = RustCryptoEcdsa::<SigningKey, VerifyingKey, Signature, P256>::new();
+        let signature = sign_message(
+            &mut backend,
+            &P256,
+            &private_key,
+            &mut Sha256Context::new(),
+            "sample",
+        )
+        .unwrap();
+        assert_eq!(signature.to_bytes()[..], hex(SAMPLE));
+
+        let mut engine = EcdsaEngine::<P256, _>::new(CountingRng(0));
+        let public_key = engine
+            .validate(&P256, *private_key.verifying_key())
+            .unwrap();
+        verify_message(
+            &mut engine,
+            &P256,
+            &public_key,
+            &mut Sha256Context::new(),
+            "sample",
+            &signature,
+        )
+        .unwrap();
+        let public_key = backend
+            .validate(&P256, *private_key.verifying_key())
+            .unwrap();
+        verify_message(
+            &mut backend,
+            &P256,
+            &public_key,
+            &mut Sha256Context::new(),
+            "sample",
+            &signature,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn rustcrypto_backend_validates_points() {
+        use p256::AffinePoint;
+
+        // Identity and off-curve points do not decode.
+        let public_key = *SigningKey::from_slice(&hex(KEY)).unwrap().verifying_key();
+        let mut off_curve = public_key.to_encoded_point(false).as_bytes().to_vec();
+        off_curve[64] ^= 1;
+        assert!(VerifyingKey::from_sec1_bytes(&off_curve).is_err());
+        assert!(VerifyingKey::from_sec1_bytes(&[0]).is_err());
+        assert!(VerifyingKey::from_affine(AffinePoint::IDENTITY).is_err());
+
+        let mut backend = RustCryptoEcdsa::<SigningKey, VerifyingKey, Signature, P256>::new();
+        assert_eq!(*backend.validate(&P256, public_key).unwrap(), public_key);
+
+        // A key of another curve than the backend's.
+        let p384_key = *p384::ecdsa::SigningKey::from_slice(&[1; 48])
+            .unwrap()
+            .verifying_key();
+        let mut backend = RustCryptoEcdsa::<
+            SigningKey,
+            p384::ecdsa::VerifyingKey,
+            p384::ecdsa::Signature,
+            P256,
+        >::new();
+        assert_eq!(
+            backend.validate(&P256, p384_key),
+            Err(EcdsaErrorKind::InvalidPublicKey)
+        );
+    }
+}
@@ -0,0 +1,373 @@
+//! Tests of the software SHA-2 backend.
+
+#![cfg(feature = "sw-sha2")]
+
+mod common;
+
+use common::hex;
+use peripheral_traits::digest::{
+    Digest, DigestAlgorithm, DigestInit, DigestRestore, DigestSave, ErrorKind, Sha2_224, Sha2_256,
+    Sha2_384, Sha2_512,
+};
+use peripheral_traits::sw::sha2::Sha2Engine;
+
+fn message(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 + 3) as u8).collect()
+}
+
+fn hash<A: DigestAlgorithm>(algorithm: A, message: &[u8]) -> Vec<u8>
+where
+    Sha2Engine: DigestInit<A>,
+{
+    let mut engine = Sha2Engine;
+    let mut digest = engine.init(algorithm).unwrap();
+    digest.update(message).unwrap();
+    digest.finalize().unwrap().as_ref().to_vec()
+}
+
+/// Checks `message` against `expected`, hashed in one piece, byte by byte, and
+/// in pieces of varying size.
+fn check<A: DigestAlgorithm + Copy>(algorithm: A, message: &[u8], expected: &str)
+where
+    Sha2Engine: DigestInit<A>,
+{
+    let expected = hex(expected);
+    assert_eq!(
+        hash(algorithm, message),
+        expected,
+        "{} bytes",
+        message.len()
+    );
+
+    let mut engine = Sha2Engine;
+    let mut digest = engine.init(algorithm).unwrap();
+    for byte in message.chunks(1) {
+        digest.update(byte).unwrap();
+    }
+    assert_eq!(digest.finalize().unwrap().as_ref(), expected);
+
+    let mut rest = message;
+    let mut size = 0;
+    while !rest.is_empty() {
+        size = size % 150 + 1;
+        let (piece, tail) = rest.split_at(size.min(rest.len()));
+        digest.update(piece).unwrap();
+        rest = tail;
+    }
+    assert_eq!(digest.finalize().unwrap().as_ref(), expected);
+}
+
+const FIPS_448: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+const FIPS_896: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmno\
+                          ijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+/// FIPS 180-4 example messages: "abc", the 448-bit and 896-bit messages, and a
+/// million times "a".
+#[test]
+fn fips_180_4_examples() {
+    let million = vec![b'a'; 1_000_000];
+
+    check(
+        Sha2_224,
+        b"abc",
+        "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7",
+    );
+    check(
+        Sha2_224,
+        FIPS_448,
+        "75388b16512776cc5dba5da1fd890150b0c6455cb4f58b1952522525",
+    );
+    check(
+        Sha2_224,
+        &million,
+        "20794655980c91d8bbb4c1ea97618a4bf03f42581948b2ee4ee7ad67",
+    );
+
+    check(
+        Sha2_256,
+        b"abc",
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    );
+    check(
+        Sha2_256,
+        FIPS_448,
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+    );
+    check(
+        Sha2_256,
+        &million,
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+    );
+
+    check(
+        Sha2_384,
+        b"abc",
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+         8086072ba1e7cc2358baeca134c825a7",
+    );
+    check(
+        Sha2_384,
+        FIPS_896,
+        "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712\
+         fcc7c71a557e2db966c3e9fa91746039",
+    );
+    check(
+        Sha2_384,
+        &million,
+        "9d0e1809716474cb086e834e310a4a1ced149e9c00f248527972cec5704c2a5b\
+         07b8b3dc38ecc4ebae97ddd87f3d8985",
+    );
+
+    check(
+        Sha2_512,
+        b"abc",
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+         2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+    );
+    check(
+        Sha2_512,
+        FIPS_896,
+        "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+         501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+    );
+    check(
+        Sha2_512,
+        &million,
+        "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb\
+         de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b",
+    );
+}
+
+/// The bytes 0, 1, 2, ... of lengths around the padding boundaries: 55 bytes
+/// is the longest message whose length still fits in its last 64-byte block,
+/// 111 bytes the same for 128-byte blocks.
+fn counting(len: usize) -> Vec<u8> {
+    (0..len as u8).collect()
+}
+
+#[test]
+fn sha224_and_sha256_padding_boundaries() {
+    for (len, sha224, sha256) in [
+        (
+            0,
+            "d14a028c2a3a2bc9476102bb288234c415a2b01f828ea62ac5b3e42f",
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        ),
+        (
+            55,
+            "8991dfba74284e04dc7581c7c3e4068ff6cb7a63733361429834bb56",
+            "463eb28e72f82e0a96c0a4cc53690c571281131f672aa229e0d45ae59b598b59",
+        ),
+        (
+            56,
+            "2b2cd637c16ad7290bb067ad7d8fd04e204fa43a84366afc7130f4ef",
+            "da2ae4d6b36748f2a318f23e7ab1dfdf45acdc9d049bd80e59de82a60895f562",
+        ),
+        (
+            63,
+            "049e8dd7eab3378ce9f823bfb569e5b270235d4b7f9623606971998f",
+            "29af2686fd53374a36b0846694cc342177e428d1647515f078784d69cdb9e488",
+        ),
+        (
+            64,
+            "c37b88a3522dbf7ac30d1c68ea397ac11d4773571aed01ddab73531e",
+            "fdeab9acf3710362bd2658cdc9a29e8f9c757fcf9811603a8c447cd1d9151108",
+        ),
+        (
+            65,
+            "114b5fd665736a96585c5d5837d35250aed73c725252cbf7f8b121f6",
+            "4bfd2c8b6f1eec7a2afeb48b934ee4b2694182027e6d0fc075074f2fabb31781",
+        ),
+    ] {
+        check(Sha2_224, &counting(len), sha224);
+        check(Sha2_256, &counting(len), sha256);
+    }
+}
+
+#[test]
+fn sha384_and_sha512_padding_boundaries() {
+    for (len, sha384, sha512) in [
+        (
+            0,
+            "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da\
+             274edebfe76f65fbd51ad2f14898b95b",
+            "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+             47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+        ),
+        (
+            111,
+            "f5f9fe110d809d34029de262a01b208356caec6e054c7f926b2591f6c9780579\
+             d4b59f5578c6f531a84f158a33660cef",
+            "a1a111449b198d9b1f538bad7f3fc1022b3a5b1a5e90a0bc860de8512746cbc3\
+             1599e6c834de3a3235327af0b51ff57bf7acf1974a73014d9c3953812edc7c8d",
+        ),
+        (
+            112,
+            "33ba080ec0ccb378e4e95fed3b26c23aa1a280476e007519ee47f60cd9c5c8a6\
+             5d627259a9aa2fd33ca06d3c14ee5548",
+            "c5fbd731d19d2ae1180f001be72c2c1aaba1d7b094b3748880e24593b8e117a7\
+             50e11c1bd867cc2f96dace8c8b74abd2d5c4f236be444e77d30d1916174070b9",
+        ),
+        (
+            127,
+            "d5fcfe2fcf6b3ef375ede37c8123d9b78065fecc1d55197e2f7721e6e9a93d0b\
+             a4d7fd15f9b96dea2744df24141ba2ef",
+            "eab89674feaa34e27aebeeff3c0a4d70070bb872d5e9f186cf1dbbdee517b6e3\
+             5724d629ff025a5b07185e911ada7e3c8acf830aa0e4f71777bd2d44f504f7f0",
+        ),
+        (
+            128,
+            "ca2385773319124534111a36d0581fc3f00815e907034b90cff9c3a861e126a7\
+             41d5dfcff65a417b6d7296863ac0ec17",
+            "1dffd5e3adb71d45d2245939665521ae001a317a03720a45732ba1900ca3b835\
+             1fc5c9b4ca513eba6f80bc7b1d1fdad4abd13491cb824d61b08d8c0e1561b3f7",
+        ),
+        (
+            129,
+            "ef49ae5b9ad51433d00323528d81ea8d2e4d2b507dbd9f1cb84f952b66249a78\
+             8b1c89fcdb77a0db9f1feb901d47fc73",
+            "1d9da57fbbdab09afb3506ab2d223d06109d65c1c8ad197f50138f714bc4c3f2\
+             fe5787922639c680acad1c651f955990425954ce2cba0c5cc83f2667d878eb0f",
+        ),
+    ] {
+        check(Sha2_384, &counting(len), sha384);
+        check(Sha2_512, &counting(len), sha512);
+    }
+}
+
+/// Hashes `message`, saving the state after `split` bytes and finishing on a
+/// context restored from it.
+fn hash_resumed<A, C>(algorithm: A, message: &[u8], split: usize) -> Vec<u8>
+where
+    A: DigestAlgorithm + Copy,
+    C: DigestSave,
+    Sha2Engine: DigestRestore<A> + for<'a> DigestInit<A, OpContext<'a> = C>,
+{
+    let mut engine = Sha2Engine;
+    let mut state = [0u8; 256];
+    let len = {
+        let mut digest = engine.init(algorithm).unwrap();
+        digest.update(&message[..split]).unwrap();
+        let len = digest.save(&mut state).unwrap();
+        assert_eq!(len, C::SAVED_STATE_SIZE);
+
+        // Saving leaves the computation untouched.
+        digest.update(&message[split..]).unwrap();
+        assert_eq!(
+            digest.finalize().unwrap().as_ref(),
+            hash(algorithm, message)
+        );
+        len
+    };
+
+    let mut engine = Sha2Engine;
+    let mut digest = engine.restore(algorithm, &state[..len]).unwrap();
+    digest.update(&message[split..]).unwrap();
+    digest.finalize().unwrap().as_ref().to_vec()
+}
+
+#[test]
+fn save_and_restore_resume_the_computation() {
+    let message = message(300);
+    for split in [0, 1, 55, 63, 64, 65, 111, 127, 128, 129, 200, 300] {
+        assert_eq!(
+            hash_resumed(Sha2_256, &message, split),
+            hash(Sha2_256, &message)
+        );
+        assert_eq!(
+            hash_resumed(Sha2_512, &message, split),
+            hash(Sha2_512, &message)
+        );
+        assert_eq!(
+            hash_resumed(Sha2_224, &message, split),
+            hash(Sha2_224, &message)
+        );
+        assert_eq!(
+            hash_resumed(Sha2_384, &message, split),
+            hash(Sha2_384, &message)
+        );
+    }
+}
+
+fn saved<A, C>(algorithm: A) -> Vec<u8>
+where
+    A: DigestAlgorithm,
+    C: DigestSave,
+    Sha2Engine: for<'a> DigestInit<A, OpContext<'a> = C>,
+{
+    let mut engine = Sha2Engine;
+    let mut digest = engine.init(algorithm).unwrap();
+    digest.update(&message(100)).unwrap();
+    let mut state = [0u8; 256];
+    let len = digest.save(&mut state).unwrap();
+    state[..len].to_vec()
+}
+
+#[test]
+fn restore_rejects_invalid_states() {
+    let mut engine = Sha2Engine;
+    let sha256 = saved(Sha2_256);
+    let sha512 = saved(Sha2_512);
+
+    // Wrong tag.
+    let mut state = sha256.clone();
+    state[0] ^= 0x80;
+    assert_eq!(
+        engine.restore(Sha2_256, &state).err(),
+        Some(ErrorKind::InvalidState)
+    );
+    let mut state = sha512.clone();
+    state[0] ^= 0x80;
+    assert_eq!(
+        engine.restore(Sha2_512, &state).err(),
+        Some(ErrorKind::InvalidState)
+    );
+
+    // Wrong length.
+    for state in [
+        &sha256[..sha256.len() - 1],
+        &[sha256.clone(), vec![0]].concat(),
+        &[],
+    ] {
+        assert_eq!(
+            engine.restore(Sha2_256, state).err(),
+            Some(ErrorKind::InvalidState)
+        );
+    }
+    for state in [
+        &sha512[..sha512.len() - 1],
+        &[sha512.clone(), vec![0]].concat(),
+        &[],
+    ] {
+        assert_eq!(
+            engine.restore(Sha2_512, state).err(),
+            Some(ErrorKind::InvalidState)
+        );
+    }
+
+    // State of another algorithm, with the same layout or a different one.
+    assert_eq!(
+        engine.restore(Sha2_224, &sha256).err(),
+        Some(ErrorKind::InvalidState)
+    );
+    assert_eq!(
+        engine.restore(Sha2_384, &sha512).err(),
+        Some(ErrorKind::InvalidState)
+    );
+    assert_eq!(
+        engine.restore(Sha2_512, &sha256).err(),
+        Some(ErrorKind::InvalidState)
+    );
+    assert_eq!(
+        engine.restore(Sha2_256, &sha512).err(),
+        Some(ErrorKind::InvalidState)
+    );
+}
+
+#[test]
+fn save_needs_room_for_the_state() {
+    let mut engine = Sha2Engine;
+    let mut digest = engine.init(Sha2_256).unwrap();
+    let mut state = [0u8; 8];
+    assert_eq!(digest.save(&mut state), Err(ErrorKind::InvalidOutputSize));
+}
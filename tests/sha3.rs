@@ -0,0 +1,297 @@
+//! FIPS 202 and SP 800-185 vectors for the software SHA-3 backend.
+
+#![cfg(feature = "sw-sha3")]
+
+mod common;
+
+use common::hex;
+use peripheral_traits::digest::{
+    Digest, DigestAlgorithm, DigestInit, Sha3_224, Sha3_256, Sha3_384, Sha3_512,
+};
+use peripheral_traits::sw::sha3::Sha3Engine;
+use peripheral_traits::xof::{
+    CShake128, CShake256, Shake128, Shake256, Xof, XofAlgorithm, XofInit, XofReader,
+};
+
+/// Checks `message` against `expected`, hashed in one piece and byte by byte.
+fn check<A: DigestAlgorithm + Default>(message: &[u8], expected: &str)
+where
+    Sha3Engine: DigestInit<A>,
+{
+    let expected = hex(expected);
+    let mut engine = Sha3Engine;
+    let mut digest = DigestInit::init(&mut engine, A::default()).unwrap();
+    digest.update(message).unwrap();
+    assert_eq!(
+        digest.finalize().unwrap().as_ref(),
+        expected,
+        "{} bytes",
+        message.len()
+    );
+
+    for byte in message.chunks(1) {
+        digest.update(byte).unwrap();
+    }
+    assert_eq!(digest.finalize().unwrap().as_ref(), expected);
+}
+
+/// Absorbs `message` and squeezes `len` bytes, in one read and in reads of
+/// varying size that cross the rate boundary.
+fn squeeze<A: XofAlgorithm + Copy>(algorithm: A, message: &[u8], len: usize) -> Vec<u8>
+where
+    Sha3Engine: XofInit<A>,
+{
+    let mut engine = Sha3Engine;
+    let mut xof = XofInit::init(&mut engine, algorithm).unwrap();
+    xof.absorb(message).unwrap();
+    let mut whole = vec![0; len];
+    xof.finalize().unwrap().squeeze(&mut whole).unwrap();
+
+    let mut xof = XofInit::init(&mut engine, algorithm).unwrap();
+    xof.absorb(message).unwrap();
+    let mut reader = xof.finalize().unwrap();
+    let mut pieces = vec![0; len];
+    let mut rest = &mut pieces[..];
+    for size in [1, 5, 32, 100, 167, 168, 169, 135, 136, 137]
+        .into_iter()
+        .cycle()
+    {
+        if rest.is_empty() {
+            break;
+        }
+        let (piece, tail) = rest.split_at_mut(size.min(rest.len()));
+        reader.squeeze(piece).unwrap();
+        rest = tail;
+    }
+    assert_eq!(pieces, whole);
+    whole
+}
+
+/// The 1600-bit message of FIPS 202 examples, 200 times 0xa3.
+const A3: [u8; 200] = [0xa3; 200];
+
+fn counting(len: usize) -> Vec<u8> {
+    (0..len as u8).collect()
+}
+
+#[test]
+fn sha3_224() {
+    check::<Sha3_224>(
+        b"",
+        "6b4e03423667dbb73b6e15454f0eb1abd4597f9a1b078e3f5b5a6bc7",
+    );
+    check::<Sha3_224>(
+        b"abc",
+        "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+    );
+    check::<Sha3_224>(
+        &A3,
+        "9376816aba503f72f96ce7eb65ac095deee3be4bf9bbc2a1cb7e11e0",
+    );
+    // The rate is 144 bytes.
+    check::<Sha3_224>(
+        &counting(143),
+        "64d0e8a1be3cf30ef6727b30a6e428f7f068d44634c943d277ad8e7f",
+    );
+    check::<Sha3_224>(
+        &counting(144),
+        "5be75e6a08f19913a1d8036c056cc4556b98dc90aeca3f2a0664dedc",
+    );
+    check::<Sha3_224>(
+        &counting(145),
+        "90b861ac1b1598459ad8337afa9933ce2f1a6f972c57daf8fc2737e4",
+    );
+}
+
+#[test]
+fn sha3_256() {
+    check::<Sha3_256>(
+        b"",
+        "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+    );
+    check::<Sha3_256>(
+        b"abc",
+        "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+    );
+    check::<Sha3_256>(
+        &A3,
+        "79f38adec5c20307a98ef76e8324afbfd46cfd81b22e3973c65fa1bd9de31787",
+    );
+    // The rate is 136 bytes.
+    check::<Sha3_256>(
+        &counting(135),
+        "fded8fd9d6551c601eeb3b7c6bc5e5cfd8aad1d015b7e9aaa9c9b9475231d5e2",
+    );
+    check::<Sha3_256>(
+        &counting(136),
+        "cf3ccff92480a29160c2d38317c430e14749bfee1788106957dfe73f8c4930e5",
+    );
+    check::<Sha3_256>(
+        &counting(137),
+        "ce9d7dc90913ee5d92745019479a5352c6d6279bef18ed07dc0a83ee8084daca",
+    );
+}
+
+#[test]
+fn sha3_384() {
+    check::<Sha3_384>(
+        b"",
+        "0c63a75b845e4f7d01107d852e4c2485c51a50aaaa94fc61995e71bbee983a2a\
+         c3713831264adb47fb6bd1e058d5f004",
+    );
+    check::<Sha3_384>(
+        b"abc",
+        "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b2\
+         98d88cea927ac7f539f1edf228376d25",
+    );
+    check::<Sha3_384>(
+        &A3,
+        "1881de2ca7e41ef95dc4732b8f5f002b189cc1e42b74168ed1732649ce1dbcdd\
+         76197a31fd55ee989f2d7050dd473e8f",
+    );
+    // The rate is 104 bytes.
+    check::<Sha3_384>(
+        &counting(103),
+        "1f91ee551ad18f268876d1fc262f137fe196580216c5193819a95ec5222537d2\
+         a658dd129c3d8080e65ec7460f1f4704",
+    );
+    check::<Sha3_384>(
+        &counting(104),
+        "5b8d0d5cf8b41be507be8fcbfcbdbac3a28eb368d430fed6780aaa78a93a8da4\
+         a6c50485949ca344f228be91a96005a3",
+    );
+    check::<Sha3_384>(
+        &counting(105),
+        "4a2f0a8f2f1f4cc4605cc2537e0be28cf8b465c30f0a54b494a7128ec54ee4e8\
+         5706b5e47a5697344d15cbf85680cd40",
+    );
+}
+
+#[test]
+fn sha3_512() {
+    check::<Sha3_512>(
+        b"",
+        "a69f73cca23a9ac5c8b567dc185a756e97c982164fe25859e0d1dcc1475c80a6\
+         15b2123af1f5f94c11e3e9402c3ac558f500199d95b6d3e301758586281dcd26",
+    );
+    check::<Sha3_512>(
+        b"abc",
+        "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+         10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+    );
+    check::<Sha3_512>(
+        &A3,
+        "e76dfad22084a8b1467fcf2ffa58361bec7628edf5f3fdc0e4805dc48caeeca8\
+         1b7c13c30adf52a3659584739a2df46be589c51ca1a4a8416df6545a1ce8ba00",
+    );
+    // The rate is 72 bytes.
+    check::<Sha3_512>(
+        &counting(71),
+        "3ccc850d53a1287af7b4560b2ef0d43eb5d9a80d62a0e9cf1dbc040135921104\
+         d4395168e90bfc871773ebb34bca1bd67056e1cc7dc7a48ff7c3167d389f117c",
+    );
+    check::<Sha3_512>(
+        &counting(72),
+        "5d63f2bbe971a983ac6847480106e4e1264ee3a0befd79954914e1d86e795b2e\
+         18238f12fc5e46cb9cc78efdec610a93647cc04e1c23d8caaa6a58c21dd26c07",
+    );
+    check::<Sha3_512>(
+        &counting(73),
+        "921d9b7b2b0f3066a1646dbb058c979cb3925dec0f8c269faaa7f9648e73465a\
+         e55ec527257d5d5e1cfdbf5d6799bea1004b6186f5108c74e3b92fe924166558",
+    );
+}
+
+const SHAKE128_A3: &str = "131ab8d2b594946b9c81333f9bb6e0ce75c3b93104fa3469d3917457385da037\
+                               cf232ef7164a6d1eb448c8908186ad852d3f85a5cf28da1ab6fe343817197846\
+                               7f1c05d58c7ef38c284c41f6c2221a76f12ab1c04082660250802294fb871802\
+                               13fdef5b0ecb7df50ca1f8555be14d32e10f6edcde892c09424b29f597afc270\
+                               c904556bfcb47a7d40778d390923642b3cbd0579e60908d5a000c1d08b98ef93\
+                               3f806445bf87f8b009ba9e94f7266122ed7ac24e5e266c42a82fa1bbefb7b8db\
+                               0066e16a85e0493f07df4809aec084a593748ac3dde5a6d7aae1e8b6e5352b2d\
+                               71efbb47d4caeed5e6d633805d2d323e6fd81b4684b93a2677d45e7421c2c6ae\
+                               a259b855a698fd7d13477a1fe53e5a4a6197dbec5ce95f505b520bcd9570c4a8\
+                               265a7e01f89c0c002c59bfec6cd4a5c109258953ee5ee70cd577ee217af21fa7\
+                               0178f0946c9bf6ca8751793479f6b537737e40b6ed28511d8a2d7e73eb75f8da\
+                               ac912ff906e0ab955b083bac45a8e5e9b744c8506f37e9b4e749a184b30f43eb\
+                               188d855f1b70d71ff3e50c537ac1b0f8974f0fe1a6ad295ba42f6aec74d123a7\
+                               abedde6e2c0711cab36be5acb1a5a11a4b1db08ba6982efccd716929a7741cfc\
+                               63aa4435e0b69a9063e880795c3dc5ef3272e11c497a91acf699fefee206227a\
+                               44c9fb359fd56ac0a9a75a743cff6862f17d7259ab075216c0699511643b6439";
+
+const SHAKE256_A3: &str = "cd8a920ed141aa0407a22d59288652e9d9f1a7ee0c1e7c1ca699424da84a904d\
+                               2d700caae7396ece96604440577da4f3aa22aeb8857f961c4cd8e06f0ae6610b\
+                               1048a7f64e1074cd629e85ad7566048efc4fb500b486a3309a8f26724c0ed628\
+                               001a1099422468de726f1061d99eb9e93604d5aa7467d4b1bd6484582a384317\
+                               d7f47d750b8f5499512bb85a226c4243556e696f6bd072c5aa2d9b69730244b5\
+                               6853d16970ad817e213e470618178001c9fb56c54fefa5fee67d2da524bb3b0b\
+                               61ef0e9114a92cdbb6cccb98615cfe76e3510dd88d1cc28ff99287512f24bfaf\
+                               a1a76877b6f37198e3a641c68a7c42d45fa7acc10dae5f3cefb7b735f12d4e58\
+                               9f7a456e78c0f5e4c4471fffa5e4fa0514ae974d8c2648513b5db494cea84715\
+                               6d277ad0e141c24c7839064cd08851bc2e7ca109fd4e251c35bb0a04fb05b364\
+                               ff8c4d8b59bc303e25328c09a882e952518e1a8ae0ff265d61c465896973d749\
+                               0499dc639fb8502b39456791b1b6ec5bcc5d9ac36a6df622a070d43fed781f5f\
+                               149f7b62675e7d1a4d6dec48c1c7164586eae06a51208c0b791244d307726505\
+                               c3ad4b26b6822377257aa152037560a739714a3ca79bd605547c9b78dd1f596f\
+                               2d4f1791bc689a0e9b799a37339c04275733740143ef5d2b58b96a363d4e0807\
+                               6a1a9d7846436e4dca5728b6f760eef0ca92bf0be5615e96959d767197a0beeb";
+
+#[test]
+fn shake128() {
+    assert_eq!(
+        squeeze(Shake128, b"", 32),
+        hex("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26")
+    );
+    assert_eq!(squeeze(Shake128, &A3, 512), hex(SHAKE128_A3));
+}
+
+#[test]
+fn shake256() {
+    assert_eq!(
+        squeeze(Shake256, b"", 32),
+        hex("46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f")
+    );
+    assert_eq!(squeeze(Shake256, &A3, 512), hex(SHAKE256_A3));
+}
+
+/// SP 800-185 cSHAKE samples 1 to 4.
+#[test]
+fn cshake() {
+    let email = b"Email Signature";
+    assert_eq!(
+        squeeze(CShake128::new(b"", email), &counting(4), 32),
+        hex("c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5")
+    );
+    assert_eq!(
+        squeeze(CShake128::new(b"", email), &counting(200), 32),
+        hex("c5221d50e4f822d96a2e8881a961420f294b7b24fe3d2094baed2c6524cc166b")
+    );
+    assert_eq!(
+        squeeze(CShake256::new(b"", email), &counting(4), 64),
+        hex(
+            "d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd1\
+             64020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c"
+        )
+    );
+    assert_eq!(
+        squeeze(CShake256::new(b"", email), &counting(200), 64),
+        hex(
+            "07dc27b11e51fbac75bc7b3c1d983e8b4b85fb1defaf218912ac864302730917\
+             27f42b17ed1df63e8ec118f04b23633c1dfb1574c8fb55cb45da8e25afb092bb"
+        )
+    );
+}
+
+#[test]
+fn cshake_without_strings_is_shake() {
+    for len in [0, 3, 168, 200] {
+        assert_eq!(
+            squeeze(CShake128::new(b"", b""), &counting(len), 200),
+            squeeze(Shake128, &counting(len), 200)
+        );
+        assert_eq!(
+            squeeze(CShake256::new(b"", b""), &counting(len), 200),
+            squeeze(Shake256, &counting(len), 200)
+        );
+    }
+}
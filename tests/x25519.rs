@@ -0,0 +1,137 @@
+//! RFC 7748 tests of the software X25519 backend.
+
+#![cfg(feature = "sw-x25519")]
+
+mod common;
+
+use common::{hex, CountingRng};
+use peripheral_traits::ecdh::{
+    Ecdh, EcdhKeyGen, EcdhTypes, EcdhValidate, ErrorKind, ErrorType, X25519,
+};
+use peripheral_traits::sw::x25519::X25519Engine;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+fn engine() -> X25519Engine<CountingRng> {
+    X25519Engine::new(CountingRng(0))
+}
+
+fn key<T: From<[u8; 32]>>(s: &str) -> T {
+    T::from(hex(s).try_into().unwrap())
+}
+
+/// RFC 7748 section 6.1.
+#[test]
+fn rfc_7748_diffie_hellman() {
+    let alice: StaticSecret =
+        key("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+    let alice_public: PublicKey =
+        key("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a");
+    let bob: StaticSecret = key("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+    let bob_public: PublicKey =
+        key("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f");
+    let shared = hex("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+    assert_eq!(PublicKey::from(&alice), alice_public);
+    assert_eq!(PublicKey::from(&bob), bob_public);
+
+    let mut engine = engine();
+    let alice_public = engine.validate(&X25519, alice_public).unwrap();
+    let bob_public = engine.validate(&X25519, bob_public).unwrap();
+    assert_eq!(
+        engine.agree(&X25519, &alice, &bob_public).unwrap()[..],
+        shared
+    );
+    assert_eq!(
+        engine.agree(&X25519, &bob, &alice_public).unwrap()[..],
+        shared
+    );
+}
+
+#[test]
+fn generated_keys_agree() {
+    let mut engine = engine();
+    let (alice, alice_public) = engine.generate_key_pair(&X25519).unwrap();
+    let (bob, bob_public) = engine.generate_key_pair(&X25519).unwrap();
+    assert_eq!(alice_public, PublicKey::from(&alice));
+    assert_ne!(alice_public, bob_public);
+
+    let alice_public = engine.validate(&X25519, alice_public).unwrap();
+    let bob_public = engine.validate(&X25519, bob_public).unwrap();
+    assert_eq!(
+        engine.agree(&X25519, &alice, &bob_public).unwrap(),
+        engine.agree(&X25519, &bob, &alice_public).unwrap()
+    );
+}
+
+/// u-coordinates of the points of order 1, 2, 4 and 8, and their encodings
+/// that are not reduced modulo p or have the top bit set.
+const SMALL_ORDER: [&str; 14] = [
+    // 0: the neutral element and the point of order 2.
+    "0000000000000000000000000000000000000000000000000000000000000000",
+    "0000000000000000000000000000000000000000000000000000000000000080",
+    // 1: order 4.
+    "0100000000000000000000000000000000000000000000000000000000000000",
+    "0100000000000000000000000000000000000000000000000000000000000080",
+    // Order 8.
+    "e0eb7a7c3b41b8ae1656e3faf19fc46ada098deb9c32b1fd866205165f49b800",
+    "e0eb7a7c3b41b8ae1656e3faf19fc46ada098deb9c32b1fd866205165f49b880",
+    "5f9c95bca3508c24b1d0b1559c83ef5b04445cc4581c8e86d8224eddd09f1157",
+    "5f9c95bca3508c24b1d0b1559c83ef5b04445cc4581c8e86d8224eddd09f11d7",
+    // p - 1, p and p + 1.
+    "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+    "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+    "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+    "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+    "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+    "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+];
+
+#[test]
+fn check_public_key_rejects_small_order_points() {
+    let mut engine = engine();
+    for point in SMALL_ORDER {
+        assert_eq!(
+            engine.validate(&X25519, key(point)),
+            Err(ErrorKind::InvalidPublicKey),
+            "{point}"
+        );
+    }
+}
+
+/// Validates every key, to reach [`Ecdh::agree`] with keys that
+/// [`X25519Engine`] would refuse.
+struct AcceptAll;
+
+impl ErrorType for AcceptAll {
+    type Error = ErrorKind;
+}
+
+impl EcdhTypes for AcceptAll {
+    type PrivateKey = StaticSecret;
+    type PublicKey = PublicKey;
+    type SharedSecret = [u8; 32];
+    type Curve = X25519;
+}
+
+impl EcdhValidate for AcceptAll {
+    fn check_public_key(
+        &mut self,
+        _curve: &X25519,
+        _public_key: &PublicKey,
+    ) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+}
+
+#[test]
+fn agree_rejects_small_order_points() {
+    let mut engine = engine();
+    let (private_key, _) = engine.generate_key_pair(&X25519).unwrap();
+    for point in SMALL_ORDER {
+        let public_key = AcceptAll.validate(&X25519, key(point)).unwrap();
+        assert_eq!(
+            engine.agree(&X25519, &private_key, &public_key),
+            Err(ErrorKind::InvalidPublicKey),
+            "{point}"
+        );
+    }
+}